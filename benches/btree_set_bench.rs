@@ -0,0 +1,42 @@
+use std::{collections::{hash_map::RandomState, BTreeSet}, hash::BuildHasher};
+use hash_on_write::How;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::random;
+
+fn random_key() -> String {
+    let len = random::<usize>() % 30;
+    let mut str = String::with_capacity(len);
+    for _ in 0..len {
+        str.push(char::from(random::<u8>() % (127-32) + 32));
+    }
+    str
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let n = 1000;
+    let repeat_count = 100;
+    let state = RandomState::new();
+    let set = std::iter::repeat_with(random_key)
+        .take(n)
+        .collect::<BTreeSet<_>>();
+    let how_set = How::new_default(set.clone());
+
+    c.bench_function("BTreeSet repeated hash", |b| {
+        b.iter(|| {
+            for _ in 0..repeat_count {
+                black_box(state.hash_one(&set));
+            }
+        })
+    });
+    c.bench_function("How<BTreeSet> repeated hash", |b| {
+        b.iter(|| {
+            for _ in 0..repeat_count {
+                black_box(state.hash_one(&how_set));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,5 +1,5 @@
-use std::{iter::repeat_with, collections::{HashMap, hash_map::DefaultHasher}, rc::Rc, sync::atomic::AtomicU64};
-use hash_on_write::How;
+use std::{iter::repeat_with, collections::HashMap, rc::Rc};
+use hash_on_write::aliases::SyncHow;
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::random;
@@ -36,7 +36,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| {
             let wrapped_keys = repeat_with(random_key)
                 .take(n)
-                .map(How::<_, DefaultHasher, AtomicU64>::new)
+                .map(SyncHow::<_>::new)
                 .map(Rc::new)
                 .collect::<Vec<_>>();
 
@@ -51,5 +51,31 @@ fn criterion_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+#[cfg(feature = "rand")]
+fn random_hashed_benchmark(c: &mut Criterion) {
+    use hash_on_write::How;
+
+    let n = 10000;
+    let repeat_count = 50;
+    let mut rng = rand::thread_rng();
+    let keys = repeat_with(|| How::random_hashed(&mut rng))
+        .take(n)
+        .collect::<Vec<SyncHow<[u64; 8]>>>();
+
+    c.bench_function("prehashed array key", |b| {
+        b.iter(|| {
+            #[allow(clippy::mutable_key_type)]
+            let mut map = HashMap::with_capacity(n);
+            for _ in 0..repeat_count {
+                for k in &keys {
+                    map.insert(k, ());
+                }
+            }
+        })
+    });
+}
+#[cfg(not(feature = "rand"))]
+fn random_hashed_benchmark(_: &mut Criterion) { }
+
+criterion_group!(benches, criterion_benchmark, random_hashed_benchmark);
 criterion_main!(benches);
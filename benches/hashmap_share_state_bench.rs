@@ -1,10 +1,9 @@
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap},
+    collections::HashMap,
     iter::repeat_with,
     ops::Deref,
-    sync::{atomic::AtomicU64, Arc},
 };
-use hash_on_write::How;
+use hash_on_write::aliases::SharedHow;
 
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::random;
@@ -44,7 +43,7 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| {
             let keys = keys.iter()
                 .map(Deref::deref)
-                .map(How::<_, DefaultHasher, Arc<AtomicU64>>::new)
+                .map(SharedHow::<_>::new)
                 .collect::<Vec<_>>();
 
             #[allow(clippy::mutable_key_type)]
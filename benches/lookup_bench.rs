@@ -0,0 +1,68 @@
+use std::{collections::{hash_map::RandomState, HashMap}, hash::BuildHasher};
+use hash_on_write::{aliases::LocalHow, Borrowed, How, PassthroughBuildHasher};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::random;
+
+fn random_key(len: usize) -> String {
+    let mut str = String::with_capacity(len);
+    for _ in 0..len {
+        str.push(char::from(random::<u8>() % (127-32) + 32));
+    }
+    str
+}
+
+fn lookup_str(map: &HashMap<String, usize>, keys: &[String]) -> usize {
+    keys.iter()
+        .map(|k| map[k.as_str()])
+        .sum()
+}
+
+#[allow(clippy::mutable_key_type)]
+fn lookup_how<S: BuildHasher>(map: &HashMap<LocalHow<String>, usize, S>, keys: &[String]) -> usize {
+    keys.iter()
+        .map(|k| map[Borrowed::make_ref(k.as_str())])
+        .sum()
+}
+
+#[allow(clippy::mutable_key_type)]
+fn how_map<S: BuildHasher + Default>(keys: &[String]) -> HashMap<LocalHow<String>, usize, S> {
+    keys.iter()
+        .cloned()
+        .map(How::new)
+        .zip(0..)
+        .collect()
+}
+
+#[allow(clippy::mutable_key_type)]
+fn criterion_benchmark(c: &mut Criterion) {
+    let n = 1000;
+    let mut group = c.benchmark_group("lookup");
+
+    for len in [8, 64, 1024] {
+        let keys = std::iter::repeat_with(|| random_key(len))
+            .take(n)
+            .collect::<Vec<_>>();
+        group.throughput(Throughput::Bytes((len * n) as u64));
+
+        let map: HashMap<String, usize> = keys.iter().cloned().zip(0..).collect();
+        group.bench_with_input(BenchmarkId::new("String", len), &keys, |b, keys| {
+            b.iter(|| lookup_str(&map, keys))
+        });
+
+        let map = how_map::<RandomState>(&keys);
+        group.bench_with_input(BenchmarkId::new("How", len), &keys, |b, keys| {
+            b.iter(|| lookup_how(&map, keys))
+        });
+
+        let map = how_map::<PassthroughBuildHasher>(&keys);
+        group.bench_with_input(BenchmarkId::new("How passthrough", len), &keys, |b, keys| {
+            b.iter(|| lookup_how(&map, keys))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
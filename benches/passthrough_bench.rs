@@ -0,0 +1,48 @@
+use std::{collections::{hash_map::RandomState, HashMap}, hash::BuildHasher};
+use hash_on_write::{aliases::SyncHow, PassthroughBuildHasher};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::random;
+
+fn random_key() -> String {
+    let len = random::<usize>() % 300;
+    let mut str = String::with_capacity(len);
+    for _ in 0..len {
+        str.push(char::from(random::<u8>() % (127-32) + 32));
+    }
+    str
+}
+
+#[allow(clippy::mutable_key_type)]
+fn lookup_all<S: BuildHasher + Default>(keys: &[SyncHow<String>], repeat_count: usize) -> usize {
+    let mut map: HashMap<_, _, S> = HashMap::with_capacity_and_hasher(keys.len(), S::default());
+    for (i, k) in keys.iter().enumerate() {
+        map.insert(k, i);
+    }
+    let mut sum = 0;
+    for _ in 0..repeat_count {
+        for k in keys {
+            sum += map[&k];
+        }
+    }
+    sum
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let n = 10000;
+    let repeat_count = 50;
+    let keys = std::iter::repeat_with(random_key)
+        .take(n)
+        .map(SyncHow::<_>::new)
+        .collect::<Vec<_>>();
+
+    c.bench_function("double hash lookup", |b| {
+        b.iter(|| lookup_all::<RandomState>(&keys, repeat_count))
+    });
+    c.bench_function("passthrough lookup", |b| {
+        b.iter(|| lookup_all::<PassthroughBuildHasher>(&keys, repeat_count))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
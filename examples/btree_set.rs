@@ -0,0 +1,27 @@
+//! Cache the hash of an ordered set, used as a key of sets of sets
+
+use std::collections::{BTreeSet, HashMap};
+use hash_on_write::{Borrowed, How};
+
+#[allow(clippy::mutable_key_type)]
+fn main() {
+    let mut groups: HashMap<How<BTreeSet<&str>>, &str> = HashMap::new();
+
+    let mut admins = How::new_default(BTreeSet::from(["alice", "bob"]));
+    // The set hash is computed once, clones and later lookups reuse it
+    How::make_hash(&admins);
+    groups.insert(admins.clone(), "admins");
+    groups.insert(How::new_default(BTreeSet::from(["carol"])), "guests");
+    assert_eq!(groups.get(&admins), Some(&"admins"));
+
+    // Iterates in sorted order, so insertion order does not affect the hash
+    let probe = BTreeSet::from(["bob", "alice"]);
+    assert_eq!(groups.get(Borrowed::make_ref(&probe)), Some(&"admins"));
+
+    // Mutation clears the cache
+    How::make_mut(&mut admins).insert("dave");
+    assert!(! How::is_hashed(&admins));
+    assert_eq!(groups.get(&admins), None);
+
+    println!("{groups:?}");
+}
@@ -0,0 +1,70 @@
+//! Hash and Eq invariants of [`How`] and [`Borrowed`] over storers and cache states,
+//! run by `cargo +nightly fuzz run hash_eq`
+//!
+//! [`Borrowed`]: hash_on_write::Borrowed
+
+#![no_main]
+
+use std::{
+    cell::Cell,
+    collections::hash_map::DefaultHasher,
+    hash::BuildHasherDefault,
+    sync::{atomic::AtomicU32, Arc},
+};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use hash_on_write::{
+    invariants::{check_pair, check_set, SetOp},
+    HashStorer, How, NoneStorer,
+};
+use libfuzzer_sys::fuzz_target;
+
+fn lossy_string(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Fixed keys, so that crashes are reproducible
+type State = BuildHasherDefault<DefaultHasher>;
+
+fn run<S>(u: &mut Unstructured<'_>, state: &State) -> Result<()>
+where S: HashStorer + Default + Clone,
+{
+    let a: How<Vec<u8>, DefaultHasher, S> = Arbitrary::arbitrary(u)?;
+    let b: How<Vec<u8>, DefaultHasher, S> = if bool::arbitrary(u)? {
+        let b = a.clone();
+        if bool::arbitrary(u)? { How::make_hash(&b); }
+        b
+    } else {
+        Arbitrary::arbitrary(u)?
+    };
+    check_pair(state, &a, &b);
+
+    let strings = [&a, &b].map(|x| {
+        let s = How::<_, DefaultHasher, S>::new(lossy_string(How::into_inner(x.clone())));
+        if How::is_hashed(x) { How::make_hash(&s); }
+        s
+    });
+    check_pair(state, &strings[0], &strings[1]);
+
+    let ops: Vec<SetOp<How<Vec<u8>, DefaultHasher, S>>> = Arbitrary::arbitrary(u)?;
+    check_set(state, ops.iter().cloned());
+    let to_string = |x| How::<_, DefaultHasher, S>::new(lossy_string(How::into_inner(x)));
+    check_set(state, ops.into_iter().map(|op| match op {
+        SetOp::Insert(x) => SetOp::Insert(to_string(x)),
+        SetOp::Lookup(x) => SetOp::Lookup(to_string(x)),
+        SetOp::Remove(x) => SetOp::Remove(to_string(x)),
+    }));
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let mut u = Unstructured::new(data);
+    let state = State::default();
+    let _ = match u.int_in_range(0..=4u8) {
+        Ok(0) => run::<Cell<u64>>(&mut u, &state),
+        Ok(1) => run::<Cell<u8>>(&mut u, &state),
+        Ok(2) => run::<Cell<Option<u16>>>(&mut u, &state),
+        Ok(3) => run::<NoneStorer>(&mut u, &state),
+        Ok(_) => run::<Arc<AtomicU32>>(&mut u, &state),
+        Err(_) => return,
+    };
+});
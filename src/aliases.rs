@@ -0,0 +1,87 @@
+//! Common [`How`] type aliases
+//!
+//! [`LocalHow`] is not [`Sync`]
+//!
+//! ```compile_fail
+//! # use hash_on_write::aliases::LocalHow;
+//! fn assert_sync<T: Sync>() {}
+//! assert_sync::<LocalHow<String>>();
+//! ```
+//!
+//! [`How`]: crate::How
+
+use core::{
+    cell::Cell,
+    sync::atomic::AtomicU64,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    sync::Arc,
+};
+
+use crate::{How, NoneStorer};
+
+/// Thread local cache, [`Send`] but not [`Sync`]
+pub type LocalHow<T, H = DefaultHasher> = How<T, H, Cell<u64>>;
+
+/// Atomic cache, [`Send`] and [`Sync`]
+pub type SyncHow<T, H = DefaultHasher> = How<T, H, AtomicU64>;
+
+/// Atomic cache shared between clones, [`Send`] and [`Sync`]
+pub type SharedHow<T, H = DefaultHasher> = How<T, H, Arc<AtomicU64>>;
+
+/// Do not cache, hashing occurs every time
+pub type UncachedHow<T, H = DefaultHasher> = How<T, H, NoneStorer>;
+
+/// [`SmolStr`] key, cheap to clone, lookup by [`Borrowed<str>`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{aliases::SmolHow, Borrowed};
+/// # use std::collections::HashMap;
+/// let mut map: HashMap<SmolHow, i32> = HashMap::new();
+/// map.insert(SmolHow::<_>::new("foo".into()), 1);
+/// assert_eq!(map.get(Borrowed::make_ref("foo")), Some(&1));
+/// ```
+///
+/// [`SmolStr`]: smol_str::SmolStr
+/// [`Borrowed<str>`]: crate::Borrowed
+#[cfg(feature = "smol_str")]
+pub type SmolHow<H = DefaultHasher, S = Cell<u64>> = How<smol_str::SmolStr, H, S>;
+
+/// [`CompactString`] key, mutable small string, lookup by [`Borrowed<str>`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{aliases::CompactHow, Borrowed, How};
+/// # use std::collections::HashSet;
+/// let mut x: CompactHow = How::new("foo".into());
+/// How::make_hash(&x);
+/// How::make_mut(&mut x).push_str("bar");
+/// assert!(! How::is_hashed(&x));
+///
+/// let set = HashSet::from([x]);
+/// assert!(set.contains(Borrowed::make_ref("foobar")));
+/// ```
+///
+/// [`CompactString`]: compact_str::CompactString
+/// [`Borrowed<str>`]: crate::Borrowed
+#[cfg(feature = "compact_str")]
+pub type CompactHow<H = DefaultHasher, S = Cell<u64>> = How<compact_str::CompactString, H, S>;
+
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Neither [`Send`] nor [`Sync`]
+    struct NonSendHasher(core::marker::PhantomData<*const ()>);
+
+    assert_send::<LocalHow<String>>();
+    assert_send_sync::<SyncHow<String>>();
+    assert_send_sync::<SharedHow<String>>();
+    assert_send_sync::<UncachedHow<String>>();
+    assert_send::<LocalHow<String, NonSendHasher>>();
+    assert_send_sync::<SyncHow<String, NonSendHasher>>();
+    assert_send_sync::<UncachedHow<String, NonSendHasher>>();
+    assert_send_sync::<crate::FrozenHow<String, NonSendHasher>>();
+};
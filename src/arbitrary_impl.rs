@@ -0,0 +1,38 @@
+use arbitrary::{size_hint, Arbitrary, MaxRecursionReached, Result, Unstructured};
+use core::hash::{Hash, Hasher};
+
+use crate::{HashStorer, How};
+
+/// Arbitrarily decide whether to prehash the value,
+/// so that both cached and uncached states get explored
+impl<'a, T, H, S> Arbitrary<'a> for How<T, H, S>
+where T: Arbitrary<'a> + Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let prehash = bool::arbitrary(u)?;
+        let how = Self::new(T::arbitrary(u)?);
+        if prehash {
+            Self::make_hash(&how);
+        }
+        Ok(how)
+    }
+
+    fn arbitrary_take_rest(mut u: Unstructured<'a>) -> Result<Self> {
+        let prehash = bool::arbitrary(&mut u)?;
+        let how = Self::new(T::arbitrary_take_rest(u)?);
+        if prehash {
+            Self::make_hash(&how);
+        }
+        Ok(how)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        size_hint::and(bool::size_hint(depth), T::size_hint(depth))
+    }
+
+    fn try_size_hint(depth: usize) -> Result<(usize, Option<usize>), MaxRecursionReached> {
+        Ok(size_hint::and(bool::try_size_hint(depth)?, T::try_size_hint(depth)?))
+    }
+}
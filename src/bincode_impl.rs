@@ -0,0 +1,46 @@
+use bincode::{
+    de::{BorrowDecoder, Decoder},
+    enc::Encoder,
+    error::{DecodeError, EncodeError},
+    BorrowDecode, Decode, Encode,
+};
+
+use crate::{Borrowed, How};
+
+/// Encode as the wrapped value
+impl<T, H, S> Encode for How<T, H, S>
+where T: ?Sized + Encode,
+{
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.value.encode(encoder)
+    }
+}
+/// Decode from the wrapped value, the hash cache is empty
+impl<Ctx, T, H, S> Decode<Ctx> for How<T, H, S>
+where T: Decode<Ctx>,
+      S: Default,
+{
+    fn decode<D: Decoder<Context = Ctx>>(decoder: &mut D) -> Result<Self, DecodeError> {
+        T::decode(decoder).map(How::new)
+    }
+}
+/// Decode from the wrapped value, the hash cache is empty
+impl<'de, Ctx, T, H, S> BorrowDecode<'de, Ctx> for How<T, H, S>
+where T: BorrowDecode<'de, Ctx>,
+      S: Default,
+{
+    fn borrow_decode<D>(decoder: &mut D) -> Result<Self, DecodeError>
+    where D: BorrowDecoder<'de, Context = Ctx>,
+    {
+        T::borrow_decode(decoder).map(How::new)
+    }
+}
+
+/// Encode as the wrapped value, same as [`How`]
+impl<T, H, S> Encode for Borrowed<T, H, S>
+where T: ?Sized + Encode,
+{
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> Result<(), EncodeError> {
+        self.value.encode(encoder)
+    }
+}
@@ -0,0 +1,138 @@
+use core::{
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+use std::collections::hash_map::DefaultHasher;
+
+use crate::{Borrowed, FromHash, HashStorer, How};
+
+/// A Bloom filter built from cached hash codes of [`How`]
+///
+/// Probe by [`Borrowed`] with the same `H` and `S`,
+/// false means the key must not exist
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, Borrowed, HowBloom};
+/// let keys = ["a", "b", "c"].map(|s| How::new_default(s.to_owned()));
+/// let bloom: HowBloom = HowBloom::from_keys(keys.iter(), 256);
+///
+/// assert!(bloom.maybe_contains(Borrowed::make_ref("a")));
+/// assert!(keys.iter().all(How::is_hashed));
+/// ```
+///
+/// [`How`]: crate::How
+/// [`Borrowed`]: crate::Borrowed
+pub struct HowBloom<H = DefaultHasher, S = Cell<u64>> {
+    _marker: PhantomData<fn() -> (H, S)>,
+    hashes: u8,
+    bits: Vec<u8>,
+}
+impl<H, S> HowBloom<H, S> {
+    const MAX_HASHES: u8 = 16;
+
+    fn indexes(&self, code: u64) -> impl Iterator<Item = usize> + 'static {
+        let len = self.bits.len() as u64 * 8;
+        let step = code.wrapping_mul(0x9E37_79B9_7F4A_7C15).rotate_left(31) | 1;
+        (0..u64::from(self.hashes)).map(move |i| {
+            (code.wrapping_add(i.wrapping_mul(step)) % len) as usize
+        })
+    }
+
+    fn insert_code(&mut self, code: u64) {
+        for i in self.indexes(code) {
+            self.bits[i / 8] |= 1 << (i % 8);
+        }
+    }
+
+    fn contains_code(&self, code: u64) -> bool {
+        self.indexes(code)
+            .all(|i| self.bits[i / 8] & (1 << (i % 8)) != 0)
+    }
+
+    /// Count of bits in filter
+    pub fn bits(&self) -> usize {
+        self.bits.len() * 8
+    }
+
+    /// Count of index functions
+    pub fn hashes(&self) -> u8 {
+        self.hashes
+    }
+
+    /// Serialize to bytes, first byte is count of index functions, rest is bit array
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.bits.len() + 1);
+        bytes.push(self.hashes);
+        bytes.extend_from_slice(&self.bits);
+        bytes
+    }
+
+    /// Deserialize from [`HowBloom::to_bytes`] output, return `None` on invalid bytes
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Option<Self> {
+        let hashes = *bytes.first()?;
+        if bytes.len() < 2 || !(1..=Self::MAX_HASHES).contains(&hashes) {
+            return None;
+        }
+        bytes.remove(0);
+        Some(Self { _marker: PhantomData, hashes, bits: bytes })
+    }
+}
+impl<H, S> HowBloom<H, S>
+where H: Hasher + Default,
+      S: HashStorer + Default,
+{
+    /// Build from keys, use cached hash codes, or compute and cache
+    ///
+    /// `bits` is rounded up to a multiple of 8, at least 8
+    pub fn from_keys<'a, T, I>(iter: I, bits: usize) -> Self
+    where T: ?Sized + Hash + 'a,
+          I: Iterator<Item = &'a How<T, H, S>>,
+          H: 'a,
+          S: 'a,
+    {
+        let codes: Vec<u64> = iter
+            .map(|key| How::make_hash(key).to_u64())
+            .collect();
+        let len = bits.div_ceil(8).max(1);
+        let per_key = (len * 8) as f64 / codes.len().max(1) as f64;
+        let hashes = (per_key * core::f64::consts::LN_2).round()
+            .clamp(1.0, Self::MAX_HASHES.into()) as u8;
+
+        let mut bloom = Self {
+            _marker: PhantomData,
+            hashes,
+            bits: vec![0; len],
+        };
+        for code in codes {
+            bloom.insert_code(code);
+        }
+        bloom
+    }
+
+    /// Returns `false` if the key must not be in keys
+    pub fn maybe_contains<Q>(&self, key: &Borrowed<Q, H, S>) -> bool
+    where Q: ?Sized + Hash,
+    {
+        self.contains_code(S::hash_one::<Q, H>(&key.value).to_u64())
+    }
+}
+impl<H, S> Clone for HowBloom<H, S> {
+    fn clone(&self) -> Self {
+        Self {
+            _marker: PhantomData,
+            hashes: self.hashes,
+            bits: self.bits.clone(),
+        }
+    }
+}
+impl<H, S> Debug for HowBloom<H, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HowBloom")
+            .field("hashes", &self.hashes)
+            .field("bits", &self.bits())
+            .finish()
+    }
+}
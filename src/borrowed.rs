@@ -4,12 +4,13 @@ use core::{
     fmt::{self, Debug, Formatter},
     hash::{Hash, Hasher},
     marker::PhantomData,
-    mem::transmute,
     ops::{Deref, DerefMut},
 };
 use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "bytemuck")]
+use bytemuck::TransparentWrapper;
 
-use crate::HashStorer;
+use crate::{HashStorer, How};
 
 /// A transparent hash wrapper, hash with behavior like [`How`]
 ///
@@ -24,11 +25,21 @@ use crate::HashStorer;
 /// assert!(set.contains(Borrowed::make_ref("a")));
 /// ```
 ///
+/// No hasher or storer value is stored, so auto traits only depend on `T`,
+/// e.g lookup keys can be shared across threads whatever the storer
+///
+/// ```
+/// # use hash_on_write::Borrowed;
+/// # use std::{cell::Cell, rc::Rc};
+/// fn assert_send_sync<T: ?Sized + Send + Sync>() {}
+/// assert_send_sync::<Borrowed<str, std::collections::hash_map::DefaultHasher, Rc<Cell<u64>>>>();
+/// ```
+///
 /// [`How`]: crate::How
 #[repr(transparent)]
 pub struct Borrowed<T: ?Sized, H = DefaultHasher, S = Cell<u64>> {
-    _hasher: PhantomData<H>,
-    _state: PhantomData<S>,
+    _hasher: PhantomData<fn() -> H>,
+    _state: PhantomData<fn() -> S>,
     pub value: T,
 }
 impl<T, H, S> Borrowed<T, H, S> {
@@ -51,6 +62,16 @@ impl<T, H, S> Borrowed<T, H, S> {
         }
     }
 }
+/// # Examples
+/// ```
+/// # use hash_on_write::Borrowed;
+/// assert_eq!(Borrowed::<String>::default().value, "");
+/// ```
+impl<T: Default, H, S> Default for Borrowed<T, H, S> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
 impl<T: ?Sized, H, S> Borrowed<T, H, S> {
     /// transmute reference to [`Borrowed`] reference
     ///
@@ -63,8 +84,9 @@ impl<T: ?Sized, H, S> Borrowed<T, H, S> {
     ///
     /// [`Borrowed`]: crate::Borrowed
     #[inline]
-    pub fn make_ref(value: &T) -> &Self {
-        unsafe { transmute(value) }
+    pub const fn make_ref(value: &T) -> &Self {
+        // SAFETY: repr(transparent) over `T`, pointer cast keeps the metadata
+        unsafe { &*(value as *const T as *const Self) }
     }
 
     /// transmute mutable reference to [`Borrowed`] mutable reference
@@ -79,10 +101,32 @@ impl<T: ?Sized, H, S> Borrowed<T, H, S> {
     ///
     /// [`Borrowed`]: crate::Borrowed
     #[inline]
-    pub fn make_mut(value: &mut T) -> &mut Self {
-        unsafe { transmute(value) }
+    pub const fn make_mut(value: &mut T) -> &mut Self {
+        // SAFETY: repr(transparent) over `T`, pointer cast keeps the metadata
+        unsafe { &mut *(value as *mut T as *mut Self) }
     }
 }
+/// # Examples
+/// ```
+/// # use hash_on_write::Borrowed;
+/// # use bytemuck::TransparentWrapper;
+/// let keys = ["a".to_owned(), "b".to_owned()];
+/// let borrowed: &[Borrowed<String>] = TransparentWrapper::wrap_slice(&keys);
+/// assert_eq!(borrowed[1].value, "b");
+/// ```
+#[cfg(feature = "bytemuck")]
+unsafe impl<T: ?Sized, H, S> TransparentWrapper<T> for Borrowed<T, H, S> { }
+
+const _: () = {
+    const fn assert_send_sync<T: ?Sized + Send + Sync>() {}
+
+    /// Neither [`Send`] nor [`Sync`]
+    struct NonSendHasher(PhantomData<*const ()>);
+
+    assert_send_sync::<Borrowed<str, NonSendHasher>>();
+    assert_send_sync::<Borrowed<[u8], DefaultHasher, std::rc::Rc<Cell<u64>>>>();
+    assert_send_sync::<Borrowed<String, NonSendHasher, Cell<u64>>>();
+};
 impl<T: ?Sized, H, S> AsRef<Self> for Borrowed<T, H, S> {
     fn as_ref(&self) -> &Self {
         self
@@ -110,7 +154,7 @@ impl<T: ?Sized + Debug, H, S> Debug for Borrowed<T, H, S> {
             .finish()
     }
 }
-impl<T: ?Sized + Copy, H, S> Copy for Borrowed<T, H, S> { }
+impl<T: Copy, H, S> Copy for Borrowed<T, H, S> { }
 impl<T: Clone, H, S> Clone for Borrowed<T, H, S> {
     fn clone(&self) -> Self {
         Self::new(self.value.clone())
@@ -148,8 +192,7 @@ where T: ?Sized + Hash,
       S: HashStorer + Default,
 {
     fn hash<H1: Hasher>(&self, state: &mut H1) {
-        S::hash_one::<_, H>(&self.value)
-            .hash(state)
+        S::write_code(S::hash_one::<_, H>(&self.value), state)
     }
 }
 impl<T: ?Sized, H, S> DerefMut for Borrowed<T, H, S> {
@@ -164,3 +207,23 @@ impl<T: ?Sized, H, S> Deref for Borrowed<T, H, S> {
         &self.value
     }
 }
+/// Owned into [`How`], the hash cache is empty
+///
+/// [`How`]: crate::How
+impl<H, S: Default> ToOwned for Borrowed<str, H, S> {
+    type Owned = How<String, H, S>;
+
+    fn to_owned(&self) -> Self::Owned {
+        How::new(self.value.to_owned())
+    }
+}
+/// Owned into [`How`], the hash cache is empty
+///
+/// [`How`]: crate::How
+impl<T: Clone, H, S: Default> ToOwned for Borrowed<[T], H, S> {
+    type Owned = How<Vec<T>, H, S>;
+
+    fn to_owned(&self) -> Self::Owned {
+        How::new(self.value.to_owned())
+    }
+}
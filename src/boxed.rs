@@ -0,0 +1,68 @@
+use core::{
+    mem::offset_of,
+    ptr::{self, NonNull},
+};
+use std::alloc::{alloc, handle_alloc_error, Layout};
+
+use crate::How;
+
+impl<T, H, S: Default> How<[T], H, S> {
+    /// New a boxed unsized [`How`] from a boxed slice
+    ///
+    /// For arrays of known length, unsizing coercion also works,
+    /// e.g `Box<How<[T; N]>>` to `Box<How<[T]>>`
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x: Box<How<[u8]>> = How::boxed_slice(vec![1, 2, 3].into_boxed_slice());
+    /// assert_eq!(x.len(), 3);
+    /// assert_eq!(How::make_hash(&x), How::make_hash(&How::new_default(vec![1u8, 2, 3])));
+    ///
+    /// let y: Box<How<[u8]>> = Box::new(How::new([1, 2, 3]));
+    /// assert_eq!(*x, *y);
+    /// ```
+    ///
+    /// [`How`]: crate::How
+    pub fn boxed_slice(value: Box<[T]>) -> Box<Self> {
+        let len = value.len();
+        // `How<[T]>` has the same field offsets as `How<[T; N]>` for any `N`,
+        // which unsizing coercion from `Box<How<[T; N]>>` relies on,
+        // so offsets of `How<[T; 0]>` are the offsets of `How<[T]>`
+        let hashcode_offset = offset_of!(How<[T; 0], H, S>, hashcode);
+        let value_offset = offset_of!(How<[T; 0], H, S>, value);
+        let align = Layout::new::<How<[T; 0], H, S>>().align();
+        let size = Layout::array::<T>(len)
+            .ok()
+            .and_then(|array| value_offset.checked_add(array.size()))
+            .expect("capacity overflow");
+        let layout = Layout::from_size_align(size, align)
+            .expect("capacity overflow")
+            .pad_to_align();
+
+        // SAFETY: `layout` is the layout of the resulting `How<[T]>`,
+        // its size is the `value` offset plus the array, padded to the struct align,
+        // which is the `Layout::for_value` the `Box` deallocates with.
+        // The storer and all `len` elements are written before creating the `Box`,
+        // the elements are moved out of `value` and its length is set to 0.
+        // Zero size layouts use a dangling pointer aligned for `How<[T; 0]>`,
+        // as `Box` does not deallocate them
+        unsafe {
+            let raw = if layout.size() == 0 {
+                NonNull::<How<[T; 0], H, S>>::dangling().as_ptr().cast::<u8>()
+            } else {
+                let raw = alloc(layout);
+                if raw.is_null() { handle_alloc_error(layout) }
+                raw
+            };
+            raw.add(hashcode_offset).cast::<S>().write(S::default());
+
+            let mut value = value.into_vec();
+            ptr::copy_nonoverlapping(value.as_ptr(), raw.add(value_offset).cast::<T>(), len);
+            value.set_len(0);
+
+            let fat = ptr::slice_from_raw_parts_mut(raw.cast::<T>(), len) as *mut Self;
+            Box::from_raw(fat)
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use bytes::{buf::UninitSlice, BufMut, BytesMut};
+
+use crate::{HashStorer, How};
+
+/// Clear the hash cache before any write
+// SAFETY: every method forwards to the sound `BytesMut` impl on the same buffer,
+// clearing the cache does not touch the buffer
+unsafe impl<H, S: HashStorer> BufMut for How<BytesMut, H, S> {
+    fn remaining_mut(&self) -> usize {
+        self.value.remaining_mut()
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        Self::make_mut(self).advance_mut(cnt)
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        Self::make_mut(self).chunk_mut()
+    }
+
+    fn put_slice(&mut self, src: &[u8]) {
+        Self::make_mut(self).put_slice(src)
+    }
+
+    fn put_bytes(&mut self, val: u8, cnt: usize) {
+        Self::make_mut(self).put_bytes(val, cnt)
+    }
+}
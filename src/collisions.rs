@@ -0,0 +1,35 @@
+//! Process-wide hash collision counter, enabled by `collisions` feature
+//!
+//! A collision is recorded when [`How`] equality finds both cached hash codes equal,
+//! but the values are not equal
+//!
+//! [`How`]: crate::How
+
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+static COLLISIONS: AtomicU64 = AtomicU64::new(0);
+static CALLBACK: RwLock<Option<fn()>> = RwLock::new(None);
+
+/// Get count of recorded collisions
+pub fn collision_count() -> u64 {
+    COLLISIONS.load(Ordering::Relaxed)
+}
+
+/// Reset collision count to zero
+pub fn reset_collision_count() {
+    COLLISIONS.store(0, Ordering::Relaxed)
+}
+
+/// Set a callback called on each recorded collision, `None` to unset
+pub fn set_collision_callback(callback: Option<fn()>) {
+    *CALLBACK.write().unwrap_or_else(|e| e.into_inner()) = callback;
+}
+
+pub(crate) fn record_collision() {
+    COLLISIONS.fetch_add(1, Ordering::Relaxed);
+    let callback = *CALLBACK.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(callback) = callback {
+        callback()
+    }
+}
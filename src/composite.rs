@@ -0,0 +1,109 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+};
+
+use crate::{BuildInnerHasher, HashStorer, How};
+
+/// A pair of [`How`], hashing by the two cached hash codes
+///
+/// Combining two pre-hashed values does not recompute their hashes,
+/// only hash the two hash codes
+///
+/// Note that the hash is not the same as hashing tuple `(A, B)`,
+/// so it can't lookup by `Borrowed<(A, B)>`
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// let a = How::new_default("foo");
+/// let b = How::new_default(2);
+/// How::make_hash(&a);
+/// How::make_hash(&b);
+///
+/// let x = How::composite(a, b);
+/// assert!(How::is_hashed(&x));
+///
+/// let y = How::composite(How::new_default("foo"), How::new_default(2));
+/// assert!(! How::is_hashed(&y));
+/// assert_eq!(How::make_hash(&x), How::make_hash(&y));
+/// assert_eq!(x, y);
+/// ```
+///
+/// [`How`]: crate::How
+pub struct CompositeHash<A, B, H, S>(pub How<A, H, S>, pub How<B, H, S>);
+
+impl<A, B, H, S> How<CompositeHash<A, B, H, S>, H, S>
+where A: Hash,
+      B: Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+{
+    /// Combine two [`How`] into a [`CompositeHash`]
+    ///
+    /// If both are cached, the combined hash code is computed immediately
+    pub fn composite(a: How<A, H, S>, b: How<B, H, S>) -> Self {
+        let both_hashed = How::is_hashed(&a) && How::is_hashed(&b);
+        let composite = How::new(CompositeHash(a, b));
+        if both_hashed {
+            How::make_hash(&composite);
+        }
+        composite
+    }
+}
+impl<A, B, H, S> CompositeHash<A, B, H, S> {
+    /// Split into two [`How`]
+    ///
+    /// [`How`]: crate::How
+    pub fn into_parts(self) -> (How<A, H, S>, How<B, H, S>) {
+        (self.0, self.1)
+    }
+}
+impl<A, B, H, S> Hash for CompositeHash<A, B, H, S>
+where A: Hash,
+      B: Hash,
+      H: BuildInnerHasher<S>,
+      S: HashStorer,
+{
+    fn hash<H1: Hasher>(&self, state: &mut H1) {
+        S::write_code(How::make_hash(&self.0), state);
+        S::write_code(How::make_hash(&self.1), state);
+    }
+}
+impl<A, B, H, S> PartialEq for CompositeHash<A, B, H, S>
+where A: PartialEq,
+      B: PartialEq,
+      H: BuildInnerHasher<S>,
+      S: HashStorer,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1 == other.1
+    }
+}
+impl<A, B, H, S> Eq for CompositeHash<A, B, H, S>
+where A: Eq,
+      B: Eq,
+      H: BuildInnerHasher<S>,
+      S: HashStorer,
+{ }
+impl<A, B, H, S> Clone for CompositeHash<A, B, H, S>
+where A: Clone,
+      B: Clone,
+      S: Clone,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone(), self.1.clone())
+    }
+}
+impl<A, B, H, S> Debug for CompositeHash<A, B, H, S>
+where A: Debug,
+      B: Debug,
+      S: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CompositeHash")
+            .field(&self.0)
+            .field(&self.1)
+            .finish()
+    }
+}
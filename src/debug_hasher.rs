@@ -0,0 +1,23 @@
+use core::hash::{Hash, Hasher};
+
+use crate::BuildInnerHasher;
+
+/// Hash the value again by a new inner hasher, panics if the result differs from `hash`
+///
+/// Catches [`Hash`] impls writing different data on two calls,
+/// and inner hashers that are not deterministic, e.g randomly seeded by [`Default`],
+/// both make cached hash codes and [`Borrowed`] lookup inconsistent.
+/// No-op in release builds
+///
+/// [`Borrowed`]: crate::Borrowed
+pub(crate) fn assert_consistent<T, H, S>(value: &T, storer: &S, hash: u64)
+where T: ?Sized + Hash,
+      H: BuildInnerHasher<S>,
+{
+    if cfg!(debug_assertions) {
+        let mut inner_hasher = H::build_inner_hasher(storer);
+        value.hash(&mut inner_hasher);
+        assert_eq!(inner_hasher.finish(), hash,
+            "inconsistent hash, the value hashed twice finished different outputs");
+    }
+}
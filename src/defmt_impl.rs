@@ -0,0 +1,24 @@
+use defmt::{Format, Formatter};
+
+use crate::{Borrowed, HashStorer, How};
+
+/// Format as the wrapped value, appending the cached hash code in hex when present
+impl<T, H, S> Format for How<T, H, S>
+where T: ?Sized + Format,
+      S: HashStorer,
+{
+    fn format(&self, fmt: Formatter<'_>) {
+        match self.hashcode.get_u64() {
+            Some(code) => defmt::write!(fmt, "{} ({=u64:#x})", &self.value, code),
+            None => defmt::write!(fmt, "{}", &self.value),
+        }
+    }
+}
+/// Format as the wrapped value
+impl<T, H, S> Format for Borrowed<T, H, S>
+where T: ?Sized + Format,
+{
+    fn format(&self, fmt: Formatter<'_>) {
+        defmt::write!(fmt, "{}", &self.value)
+    }
+}
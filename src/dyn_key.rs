@@ -0,0 +1,57 @@
+use core::{
+    any::Any,
+    hash::{Hash, Hasher},
+};
+
+/// Object safe version of [`Hash`] and [`Eq`], for heterogeneous keys
+///
+/// Implemented for all `T: Hash + Eq + 'static`,
+/// and `dyn DynKey` implemented [`Hash`] and [`Eq`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, DynKey};
+/// # use std::collections::HashSet;
+/// let mut set: HashSet<How<Box<dyn DynKey>>> = HashSet::new();
+///
+/// set.insert(How::new(Box::new(1)));
+/// set.insert(How::new(Box::new("a")));
+///
+/// assert!(set.contains(&How::new(Box::new(1) as Box<dyn DynKey>)));
+/// assert!(! set.contains(&How::new(Box::new(1u8) as Box<dyn DynKey>)));
+/// ```
+pub trait DynKey {
+    /// Like [`Hash::hash`]
+    fn dyn_hash(&self, state: &mut dyn Hasher);
+
+    /// Like [`PartialEq::eq`], return false when `other` is not same type
+    fn dyn_eq(&self, other: &dyn Any) -> bool;
+
+    /// Upcast to [`Any`]
+    fn as_any(&self) -> &dyn Any;
+}
+impl<T: Hash + Eq + 'static> DynKey for T {
+    fn dyn_hash(&self, mut state: &mut dyn Hasher) {
+        self.hash(&mut state)
+    }
+
+    fn dyn_eq(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<T>()
+            .is_some_and(|other| self == other)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+impl Hash for dyn DynKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dyn_hash(state)
+    }
+}
+impl PartialEq for dyn DynKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other.as_any())
+    }
+}
+impl Eq for dyn DynKey { }
@@ -0,0 +1,75 @@
+use core::{
+    fmt::{self, Debug, Formatter},
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU64, Ordering as MOrd},
+};
+
+use crate::HashStorer;
+
+macro_rules! exact_atomic {
+    ($($(#[$attr:meta])* $name:ident($atomic:ty: $wide:ty) => $ty:ty),+ $(,)?) => {$(
+        $(#[$attr])*
+        ///
+        /// Zero hash code is stored as is, no [`FromHash::ZERO_MAPPED`] sentinel,
+        /// by a preserved-zero flag bit beside the code,
+        /// at the cost of an atomic twice as wide as the hash code
+        ///
+        /// [`FromHash::ZERO_MAPPED`]: crate::FromHash::ZERO_MAPPED
+        #[derive(Default)]
+        pub struct $name($atomic);
+
+        impl $name {
+            /// New an empty storer
+            pub const fn new() -> Self {
+                Self(<$atomic>::new(0))
+            }
+        }
+        impl Debug for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_tuple(stringify!($name))
+                    .field(&HashStorer::get(self))
+                    .finish()
+            }
+        }
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                Self(<$atomic>::new(self.0.load(MOrd::Relaxed)))
+            }
+        }
+        impl HashStorer for $name {
+            type HashCode = $ty;
+
+            fn clear(&mut self) {
+                self.0.store(0, MOrd::Relaxed)
+            }
+
+            fn get(&self) -> Option<$ty> {
+                let n = self.0.load(MOrd::Relaxed);
+                if n == 0 { return None; }
+                Some((n >> 1) as $ty)
+            }
+
+            fn set(&self, code: $ty) {
+                self.0.store(<$wide>::from(code) << 1 | 1, MOrd::Relaxed)
+            }
+
+            fn get_or_init<F>(&self, f: F) -> $ty
+            where F: FnOnce() -> $ty,
+            {
+                HashStorer::get(self)
+                    .unwrap_or_else(|| {
+                        let n = f();
+                        HashStorer::set(self, n);
+                        n
+                    })
+            }
+        }
+    )+};
+}
+exact_atomic! {
+    /// Atomic storer of `u8` hash codes, backed by [`AtomicU16`]
+    ExactAtomicU8(AtomicU16: u16) => u8,
+    /// Atomic storer of `u16` hash codes, backed by [`AtomicU32`]
+    ExactAtomicU16(AtomicU32: u32) => u16,
+    /// Atomic storer of `u32` hash codes, backed by [`AtomicU64`]
+    ExactAtomicU32(AtomicU64: u64) => u32,
+}
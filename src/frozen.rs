@@ -0,0 +1,182 @@
+use core::{
+    borrow::Borrow,
+    cell::Cell,
+    cmp::Ordering,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::Deref,
+};
+use std::collections::hash_map::DefaultHasher;
+
+use crate::{Borrowed, HashStorer, How};
+
+/// An immutable [`How`], hash code is computed in constructor
+///
+/// Without mutation API, no cache invalidation and lazy init needed,
+/// so it is [`Sync`] when `T` is [`Sync`], regardless of `H`
+///
+/// Hash behavior like [`How`] using the same `H`
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{Borrowed, FrozenHow};
+/// # use std::collections::HashSet;
+/// let mut set: HashSet<FrozenHow<String>> = HashSet::new();
+///
+/// set.insert(FrozenHow::new("a".to_owned()));
+/// assert!(set.contains(Borrowed::make_ref("a")));
+/// assert!(! set.contains(Borrowed::make_ref("b")));
+/// ```
+///
+/// [`How`]: crate::How
+pub struct FrozenHow<T: ?Sized, H = DefaultHasher> {
+    _hasher: PhantomData<fn() -> H>,
+    hashcode: u64,
+    value: T,
+}
+impl<T, H> FrozenHow<T, H>
+where T: Hash,
+      H: Hasher + Default,
+{
+    /// New a wrapped value, and compute hash code
+    pub fn new(value: T) -> Self {
+        let hashcode = Cell::<u64>::hash_one::<T, H>(&value);
+        Self::with_code(value, hashcode)
+    }
+}
+impl<T, H> FrozenHow<T, H> {
+    fn with_code(value: T, hashcode: u64) -> Self {
+        Self {
+            _hasher: PhantomData,
+            hashcode,
+            value,
+        }
+    }
+
+    /// Consume `self` into wrapped value
+    pub fn into_inner(this: Self) -> T {
+        this.value
+    }
+}
+impl<T: ?Sized, H> FrozenHow<T, H> {
+    /// Get computed hash code
+    pub fn hash_code(this: &Self) -> u64 {
+        this.hashcode
+    }
+}
+impl<T, H, S> From<How<T, H, S>> for FrozenHow<T, H>
+where T: Hash,
+      H: Hasher + Default,
+      S: HashStorer<HashCode = u64>,
+{
+    /// Reuse the cached hash code if exists
+    fn from(value: How<T, H, S>) -> Self {
+        match How::hash_code(&value) {
+            Some(code) => Self::with_code(How::into_inner(value), code),
+            None => Self::new(How::into_inner(value)),
+        }
+    }
+}
+impl<T, H, S> From<FrozenHow<T, H>> for How<T, H, S>
+where S: HashStorer<HashCode = u64> + Default,
+{
+    /// Carry the hash code into the new [`How`] cache
+    ///
+    /// [`How`]: crate::How
+    fn from(value: FrozenHow<T, H>) -> Self {
+        let FrozenHow { hashcode, value, .. } = value;
+        let how = Self::new(value);
+        how.hashcode.get_or_init(|| hashcode);
+        how
+    }
+}
+impl<T, H> From<T> for FrozenHow<T, H>
+where T: Hash,
+      H: Hasher + Default,
+{
+    #[inline]
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T, H> Default for FrozenHow<T, H>
+where T: Hash + Default,
+      H: Hasher + Default,
+{
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+impl<T: ?Sized, H> AsRef<T> for FrozenHow<T, H> {
+    fn as_ref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T: ?Sized, H> AsRef<Self> for FrozenHow<T, H> {
+    fn as_ref(&self) -> &Self {
+        self
+    }
+}
+impl<T, Q, H> Borrow<Borrowed<Q, H>> for FrozenHow<T, H>
+where T: ?Sized + Borrow<Q>,
+      Q: ?Sized,
+{
+    fn borrow(&self) -> &Borrowed<Q, H> {
+        Borrowed::make_ref(self.value.borrow())
+    }
+}
+impl<T: ?Sized + Debug, H> Debug for FrozenHow<T, H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrozenHow")
+            .field("hashcode", &self.hashcode)
+            .field("value", &&self.value)
+            .finish()
+    }
+}
+impl<T: Clone, H> Clone for FrozenHow<T, H> {
+    fn clone(&self) -> Self {
+        Self::with_code(self.value.clone(), self.hashcode)
+    }
+}
+impl<T: Copy, H> Copy for FrozenHow<T, H> { }
+impl<T: ?Sized + PartialEq, H> PartialEq for FrozenHow<T, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hashcode == other.hashcode
+            && self.value == other.value
+    }
+}
+impl<T: ?Sized + PartialEq, H> PartialEq<T> for FrozenHow<T, H> {
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+impl<T: ?Sized + Eq, H> Eq for FrozenHow<T, H> { }
+impl<T: ?Sized + PartialOrd, H> PartialOrd for FrozenHow<T, H> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+impl<T: ?Sized + PartialOrd, H> PartialOrd<T> for FrozenHow<T, H> {
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+impl<T: ?Sized + Ord, H> Ord for FrozenHow<T, H> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+impl<T: ?Sized, H> Hash for FrozenHow<T, H> {
+    #[inline]
+    fn hash<H1: Hasher>(&self, state: &mut H1) {
+        self.hashcode.hash(state)
+    }
+}
+impl<T: ?Sized, H> Deref for FrozenHow<T, H> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
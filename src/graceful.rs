@@ -0,0 +1,88 @@
+use core::{hash::Hasher, marker::PhantomData};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::FromHash;
+
+/// Hasher wrapper catching panics of the inner hasher,
+/// returns fallback hash [`FromHash::ZERO_MAPPED`] of `C` after any panic
+///
+/// A hasher can't know the storer width,
+/// so `C` should be the [`HashStorer::HashCode`] of the storer, e.g `u8` for `Cell<u8>`
+///
+/// Only panics in the inner [`Hasher`] methods are caught,
+/// panics in [`Hash::hash`] of the value still propagate
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{FromHash, GracefulHasher, How};
+/// # use std::{cell::Cell, hash::Hasher};
+/// #[derive(Default)]
+/// struct Bad;
+/// impl Hasher for Bad {
+///     fn finish(&self) -> u64 { panic!("bad hasher") }
+///     fn write(&mut self, _: &[u8]) { }
+/// }
+/// let x: How<&str, GracefulHasher<Bad>> = How::new("foo");
+/// assert_eq!(How::make_hash(&x), <u64 as FromHash>::ZERO_MAPPED);
+///
+/// let y: How<&str, GracefulHasher<Bad, u8>, Cell<u8>> = How::new("foo");
+/// assert_eq!(How::make_hash(&y), <u8 as FromHash>::ZERO_MAPPED);
+/// ```
+///
+/// [`Hash::hash`]: core::hash::Hash::hash
+/// [`HashStorer::HashCode`]: crate::HashStorer::HashCode
+#[derive(Debug, Default, Clone)]
+pub struct GracefulHasher<H, C = u64> {
+    inner: H,
+    panicked: bool,
+    _code: PhantomData<fn() -> C>,
+}
+impl<H, C> GracefulHasher<H, C> {
+    /// Whether the inner hasher has panicked
+    pub fn panicked(&self) -> bool {
+        self.panicked
+    }
+
+    fn guard(&mut self, f: impl FnOnce(&mut H)) {
+        if self.panicked { return; }
+        let inner = &mut self.inner;
+        self.panicked = catch_unwind(AssertUnwindSafe(|| f(inner))).is_err();
+    }
+}
+impl<H: Hasher, C: FromHash> Hasher for GracefulHasher<H, C> {
+    fn finish(&self) -> u64 {
+        if self.panicked {
+            return C::ZERO_MAPPED.to_u64();
+        }
+        catch_unwind(AssertUnwindSafe(|| self.inner.finish()))
+            .unwrap_or(C::ZERO_MAPPED.to_u64())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.guard(|inner| inner.write(bytes))
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.guard(|inner| inner.write_u8(i))
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.guard(|inner| inner.write_u16(i))
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.guard(|inner| inner.write_u32(i))
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.guard(|inner| inner.write_u64(i))
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.guard(|inner| inner.write_u128(i))
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.guard(|inner| inner.write_usize(i))
+    }
+}
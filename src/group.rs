@@ -0,0 +1,37 @@
+use core::hash::{Hash, Hasher};
+
+use crate::{FromHash, HashStorer, How};
+
+/// Upper limit of `shard_bits` in [`group_by_shard`], at most 65536 shards
+pub const MAX_SHARD_BITS: u32 = 16;
+
+/// Partition values into `1 << shard_bits` shards by top bits of hash code
+///
+/// Equal values always land in the same shard,
+/// `shard_bits` is clamped to the bit width of hash code and [`MAX_SHARD_BITS`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, group_by_shard};
+/// let shards = group_by_shard(["a", "b", "a"].map(How::new_default), 2);
+/// assert_eq!(shards.len(), 4);
+/// assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), 3);
+/// ```
+pub fn group_by_shard<T, H, S, I>(iter: I, shard_bits: u32) -> Vec<Vec<How<T, H, S>>>
+where T: Hash,
+      H: Hasher + Default,
+      S: HashStorer,
+      I: IntoIterator<Item = How<T, H, S>>,
+{
+    let shard_bits = shard_bits.min(S::HashCode::BITS).min(MAX_SHARD_BITS);
+    let mut shards: Vec<Vec<_>> = (0..1usize << shard_bits)
+        .map(|_| Vec::new())
+        .collect();
+
+    for value in iter {
+        let code = How::make_hash(&value).to_u64();
+        let shard = code.checked_shr(S::HashCode::BITS - shard_bits).unwrap_or(0);
+        shards[shard as usize].push(value);
+    }
+    shards
+}
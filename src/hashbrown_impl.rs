@@ -0,0 +1,39 @@
+use core::{
+    borrow::Borrow,
+    hash::{BuildHasher, Hash, Hasher},
+};
+use hashbrown::{hash_map::RawEntryMut, HashMap};
+
+use crate::{Borrowed, HashStorer, How};
+
+/// Entry of a hashbrown [`HashMap`] keyed by [`How`], probing by [`Borrowed`]
+///
+/// No key is built on lookup, so the update-existing path never allocates,
+/// only build the owned [`How`] key on the vacant path, e.g in [`RawEntryMut::or_insert_with`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{how_entry, How};
+/// # use hashbrown::HashMap;
+/// let mut counts: HashMap<How<String>, i32> = HashMap::new();
+/// for word in ["a", "b", "a"] {
+///     let (_, count) = how_entry(&mut counts, word)
+///         .or_insert_with(|| (How::new(word.to_owned()), 0));
+///     *count += 1;
+/// }
+/// assert_eq!(counts[&How::new("a".to_owned())], 2);
+/// ```
+///
+/// [`Borrowed`]: crate::Borrowed
+pub fn how_entry<'m, T, Q, V, H, S, B>(
+    map: &'m mut HashMap<How<T, H, S>, V, B>,
+    key: &Q,
+) -> RawEntryMut<'m, How<T, H, S>, V, B>
+where T: Borrow<Q>,
+      Q: ?Sized + Hash + Eq,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      B: BuildHasher,
+{
+    map.raw_entry_mut().from_key(Borrowed::<Q, H, S>::make_ref(key))
+}
@@ -0,0 +1,104 @@
+//! Hash and Eq invariant checks, shared by tests and the fuzz target,
+//! enabled by `fuzz-support` feature, test support only, not a stable API
+//!
+//! Each check panics on a violated invariant
+//!
+//! # Examples
+//! ```
+//! # use hash_on_write::{invariants::{check_pair, check_set, SetOp}, How};
+//! # use std::collections::hash_map::RandomState;
+//! let state = RandomState::new();
+//! let a: How<String> = How::new("foo".into());
+//! let b: How<String> = How::new("foo".into());
+//! How::make_hash(&a);
+//! check_pair(&state, &a, &b);
+//!
+//! check_set(&state, [SetOp::Insert(a), SetOp::Lookup(b.clone()), SetOp::Remove(b)]);
+//! ```
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use core::{
+    fmt::Debug,
+    hash::{BuildHasher, Hash, Hasher},
+};
+use std::collections::HashSet;
+
+use crate::{Borrowed, HashStorer, How};
+
+/// Check invariants between two values
+///
+/// - `a == b` is the same as comparing values
+/// - `a == b` implies equal outer hashes
+/// - [`Borrowed`] of the value hashes equal to the [`How`]
+pub fn check_pair<T, H, S, B>(state: &B, a: &How<T, H, S>, b: &How<T, H, S>)
+where T: ?Sized + Hash + Eq,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      B: BuildHasher,
+{
+    let eq = a == b;
+    assert_eq!(eq, **a == **b, "How equality differs from value equality");
+    if eq {
+        assert_eq!(state.hash_one(a), state.hash_one(b), "equal values hash differently");
+    }
+    for x in [a, b] {
+        let borrowed: &Borrowed<T, H, S> = Borrowed::make_ref(x);
+        assert_eq!(state.hash_one(borrowed), state.hash_one(x), "Borrowed hashes differently");
+    }
+}
+
+/// Operation of [`check_set`]
+#[derive(Debug, Clone)]
+pub enum SetOp<K> {
+    /// Insert, the result must be the same as the model set
+    Insert(K),
+    /// Lookup by [`Borrowed`], the result must be the same as the model set
+    Lookup(K),
+    /// Remove by [`Borrowed`], the result must be the same as the model set
+    Remove(K),
+}
+impl<'a, K: Arbitrary<'a>> Arbitrary<'a> for SetOp<K> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let key = K::arbitrary(u)?;
+        Ok(match u.int_in_range(0..=2)? {
+            0 => Self::Insert(key),
+            1 => Self::Lookup(key),
+            _ => Self::Remove(key),
+        })
+    }
+}
+
+/// Run `ops` on a [`HashSet`] of [`How`], and a model set of values,
+/// results and lengths must always be the same
+pub fn check_set<T, H, S, B>(state: &B, ops: impl IntoIterator<Item = SetOp<How<T, H, S>>>)
+where T: Hash + Eq + Clone + Debug,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      B: BuildHasher + Clone,
+{
+    #[allow(clippy::mutable_key_type)]
+    let mut set: HashSet<How<T, H, S>, B> = HashSet::with_hasher(state.clone());
+    let mut model: HashSet<T> = HashSet::new();
+
+    for op in ops {
+        match op {
+            SetOp::Insert(key) => {
+                let value = (*key).clone();
+                assert_eq!(set.insert(key), model.insert(value), "insert");
+            },
+            SetOp::Lookup(key) => {
+                let found = set.get(Borrowed::make_ref(&*key));
+                assert_eq!(found.map(|x| &**x), model.get(&*key), "lookup");
+                assert_eq!(set.contains(&key), model.contains(&*key), "contains");
+            },
+            SetOp::Remove(key) => {
+                let removed = set.remove(Borrowed::make_ref(&*key));
+                assert_eq!(removed, model.remove(&*key), "remove");
+            },
+        }
+        assert_eq!(set.len(), model.len(), "length");
+    }
+    for value in &model {
+        assert!(set.contains(Borrowed::make_ref(value)), "lost key");
+    }
+}
@@ -1,132 +1,555 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(feature = "nightly", feature(pattern, coerce_unsized))]
 
 #[cfg(test)]
 mod tests;
+pub mod aliases;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+#[cfg(feature = "bincode")]
+mod bincode_impl;
+mod bloom;
 mod borrowed;
+mod boxed;
+#[cfg(feature = "bytes")]
+mod bytes_impl;
+#[cfg(feature = "collisions")]
+mod collisions;
+mod composite;
+#[cfg(feature = "defmt")]
+mod defmt_impl;
+#[cfg(feature = "debug-hasher")]
+mod debug_hasher;
+mod dyn_key;
+mod exact;
+mod frozen;
+mod graceful;
+mod group;
+#[cfg(feature = "fuzz-support")]
+pub mod invariants;
+#[cfg(feature = "hashbrown")]
+mod hashbrown_impl;
+mod passthrough;
+mod poison;
+#[cfg(feature = "nightly")]
+mod pattern_impl;
+pub mod prelude;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "rand")]
+mod rand_impl;
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+#[cfg(feature = "rkyv")]
+pub use rkyv_impl::ArchivedHow;
+#[cfg(feature = "schemars")]
+mod schemars_impl;
+mod seeded;
+#[cfg(feature = "serde")]
+pub mod serde_cached;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "serde")]
+pub use serde_impl::{serde_borrow_cow_str, BorrowedSeed};
+mod set_ops;
+#[cfg(feature = "bincode")]
+pub mod snapshot;
+#[cfg(feature = "sqlx")]
+mod sqlx_impl;
+#[cfg(feature = "tracing")]
+mod trace;
+mod typed;
+#[cfg(feature = "stats")]
+mod stats;
+#[cfg(feature = "nightly")]
+mod unsize;
 
+pub use bloom::HowBloom;
 pub use borrowed::Borrowed;
+#[cfg(feature = "collisions")]
+pub use collisions::{collision_count, reset_collision_count, set_collision_callback};
+pub use composite::CompositeHash;
+pub use dyn_key::DynKey;
+pub use exact::{ExactAtomicU16, ExactAtomicU32, ExactAtomicU8};
+pub use frozen::FrozenHow;
+pub use graceful::GracefulHasher;
+pub use group::{group_by_shard, MAX_SHARD_BITS};
+#[cfg(feature = "hashbrown")]
+pub use hashbrown_impl::how_entry;
+pub use seeded::{Seeded, SeededHow, StoredBuildHasher};
+pub use passthrough::{PassthroughBuildHasher, PassthroughHasher};
+pub use poison::CachePoisonDetector;
+pub use set_ops::{difference, intersection, join, JoinMode};
+pub use typed::TypeTagged;
+#[cfg(feature = "stats")]
+pub use stats::{reset_stats, stats, Stats};
 
 use core::{
     borrow::{Borrow, BorrowMut},
     cell::Cell,
     cmp::Ordering,
     fmt::{self, Debug},
-    hash::{Hash, Hasher},
+    hash::{BuildHasher, Hash, Hasher},
     marker::PhantomData,
     ops::{Deref, DerefMut},
-    sync::atomic::{AtomicU64, Ordering as MOrd},
+    sync::atomic::{AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering as MOrd},
 };
 use std::{
+    borrow::Cow,
+    io::{self, Cursor},
     rc::Rc,
     sync::Arc,
-    collections::hash_map::DefaultHasher,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
 };
 
 /// Adapters that do not store hash values
 ///
 /// Hashing occurs every time, just like [`How`] doesn't exist
 ///
+/// No value of `T` is stored, so common traits have no bounds on `T`,
+/// the [`HashStorer`] impl only requires `T` as a hash code type,
+/// implement [`FromHash`] for a custom one
+///
 /// [`How`]: crate::How
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct NoneStorer;
+pub struct NoneStorer<T = u64> {
+    _code: PhantomData<fn() -> T>,
+}
+impl<T> NoneStorer<T> {
+    /// Create a [`NoneStorer`], same as [`Default::default`]
+    pub const fn new() -> Self {
+        Self { _code: PhantomData }
+    }
+}
+impl<T> Debug for NoneStorer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("NoneStorer")
+    }
+}
+impl<T> Default for NoneStorer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Clone for NoneStorer<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for NoneStorer<T> { }
+impl<T> PartialEq for NoneStorer<T> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+impl<T> Eq for NoneStorer<T> { }
+impl<T> PartialOrd for NoneStorer<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> Ord for NoneStorer<T> {
+    fn cmp(&self, _other: &Self) -> Ordering {
+        Ordering::Equal
+    }
+}
+
+/// Hash code types of [`HashStorer`], narrowing from [`Hasher::finish`] result
+///
+/// [`HashStorer`]: crate::HashStorer
+/// [`Hasher::finish`]: core::hash::Hasher::finish
+pub trait FromHash: Copy {
+    /// Zero is used to represent uncached, real zero hash code mapping to it
+    const ZERO_MAPPED: Self;
+
+    /// Bit width of hash code
+    const BITS: u32;
+
+    /// Narrow from `u64` hash result, keep low bits
+    ///
+    /// If low bits are zero, fold from the next higher bits,
+    /// so that zero is only returned for zero `hash`,
+    /// and narrow codes are not biased towards [`ZERO_MAPPED`](FromHash::ZERO_MAPPED)
+    ///
+    /// Earlier versions truncated only, narrow codes of hashes with zero low bits differ,
+    /// narrow codes persisted by them (e.g `serde_cached`, `rkyv`, `snapshot`) are stale.
+    /// `u64` codes are unchanged
+    ///
+    /// ```
+    /// # use hash_on_write::FromHash;
+    /// assert_eq!(u8::from_hash(0x1234), 0x34);
+    /// assert_eq!(u8::from_hash(0x1200), 0x12); // was 0, cached as `ZERO_MAPPED`
+    /// assert_eq!(u64::from_hash(0x1200), 0x1200);
+    /// ```
+    fn from_hash(hash: u64) -> Self;
+
+    /// Widen to `u64` by zero-extension
+    fn to_u64(self) -> u64;
+}
+macro_rules! impl_from_hash {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl FromHash for $ty {
+            const ZERO_MAPPED: Self = <$ty>::MAX >> 2;
+            const BITS: u32 = <$ty>::BITS;
+
+            #[inline]
+            fn from_hash(mut hash: u64) -> Self {
+                while hash as $ty == 0 && hash != 0 {
+                    hash = hash.checked_shr(<$ty>::BITS).unwrap_or(0);
+                }
+                hash as $ty
+            }
+
+            #[inline]
+            fn to_u64(self) -> u64 {
+                self.into()
+            }
+        }
+    )+};
+}
+impl_from_hash!(u8, u16, u32, u64);
+
+/// Whether a cached hash code matches a freshly computed one,
+/// zero is cached as [`FromHash::ZERO_MAPPED`]
+pub(crate) fn code_matches<C: FromHash + Eq>(cached: C, fresh: C) -> bool {
+    cached == fresh
+        || fresh == C::from_hash(0) && cached == C::ZERO_MAPPED
+}
+
+/// Acquire the inner hasher of [`How`], computing the hash code of the value
+///
+/// Implemented for every [`Hasher`] + [`Default`] type, built by [`Default`],
+/// and for [`StoredBuildHasher`], built by the instance in the [`Seeded`] storer
+pub trait BuildInnerHasher<S: ?Sized> {
+    /// Hasher computing the hash code
+    type Hasher: Hasher;
+
+    /// Whether cached hash codes of two values are comparable,
+    /// false if each value may build a different hasher
+    const COMPARABLE: bool = true;
+
+    /// New an inner hasher
+    fn build_inner_hasher(storer: &S) -> Self::Hasher;
+}
+impl<H: Hasher + Default, S: ?Sized> BuildInnerHasher<S> for H {
+    type Hasher = H;
+
+    #[inline]
+    fn build_inner_hasher(_storer: &S) -> Self::Hasher {
+        H::default()
+    }
+}
+
+/// Transfer hash code between hash code types, used by [`How::convert_storer`]
+///
+/// Only the same type can be transferred, others return `None`
+///
+/// [`How::convert_storer`]: crate::How::convert_storer
+pub trait TransferCode<To> {
+    /// Transfer hash code, `None` if can't be transferred
+    fn transfer(self) -> Option<To>;
+}
+macro_rules! impl_transfer_code {
+    ($($from:ty => $to:ty : $result:ident),+ $(,)?) => {$(
+        impl TransferCode<$to> for $from {
+            #[inline]
+            fn transfer(self) -> Option<$to> {
+                impl_transfer_code!(@$result self)
+            }
+        }
+    )+};
+    (@some $code:ident) => { Some($code) };
+    (@none $code:ident) => {{ let _ = $code; None }};
+}
+impl_transfer_code! {
+    u8 => u8: some, u8 => u16: none, u8 => u32: none, u8 => u64: none,
+    u16 => u8: none, u16 => u16: some, u16 => u32: none, u16 => u64: none,
+    u32 => u8: none, u32 => u16: none, u32 => u32: some, u32 => u64: none,
+    u64 => u8: none, u64 => u16: none, u64 => u32: none, u64 => u64: some,
+}
+
+/// Mutations that do not change the hash relevant content of the value,
+/// e.g capacity operations, used through [`How::neutral_mut`]
+/// without clearing the hash cache
+///
+/// [`How::neutral_mut`]: crate::How::neutral_mut
+pub trait CacheNeutralMut {
+    /// Shrink the capacity as much as possible
+    fn shrink_to_fit(&mut self);
+
+    /// Reserve capacity for at least `additional` more elements
+    fn reserve(&mut self, additional: usize);
+}
+impl<T> CacheNeutralMut for Vec<T> {
+    fn shrink_to_fit(&mut self) {
+        Vec::shrink_to_fit(self)
+    }
 
-const ZERO_MAPPED: u64 = u64::MAX >> 2;
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional)
+    }
+}
+impl CacheNeutralMut for String {
+    fn shrink_to_fit(&mut self) {
+        String::shrink_to_fit(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        String::reserve(self, additional)
+    }
+}
+
+/// Values with an allocated capacity, used by [`How::capacity`]
+///
+/// [`How::capacity`]: crate::How::capacity
+pub trait Capacity {
+    /// Current allocated capacity
+    fn capacity(&self) -> usize;
+}
+impl<T> Capacity for Vec<T> {
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+impl Capacity for String {
+    fn capacity(&self) -> usize {
+        String::capacity(self)
+    }
+}
 
 /// storage trait for storing hash status
 pub trait HashStorer {
+    /// Stored hash code type
+    type HashCode: Hash + FromHash + Eq;
+
+    /// Whether the storer actually caches hash code
+    const CACHES: bool = true;
+
     /// Clear stored hash code to none
     fn clear(&mut self);
 
     /// Get stored hash code
-    fn get(&self) -> Option<u64>;
+    fn get(&self) -> Option<Self::HashCode>;
+
+    /// Get stored hash code, widening to `u64` by zero-extension
+    ///
+    /// Useful for keying on a uniform width regardless of storer
+    fn get_u64(&self) -> Option<u64> {
+        self.get().map(FromHash::to_u64)
+    }
+
+    /// Overwrite stored hash code, zero is mapped like [`get_or_init`]
+    ///
+    /// [`get_or_init`]: HashStorer::get_or_init
+    fn set(&self, code: Self::HashCode);
 
     /// if stored hash code is uninit, call init func
     ///
     /// return inited hash code
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64;
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode;
+
+    /// Write hash code into the outer hasher, used by [`Hash`] of [`How`] and [`Borrowed`]
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    #[inline]
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        code.hash(state)
+    }
 
-    fn hash_one<T, H>(value: &T) -> u64
+    fn hash_one<T, H>(value: &T) -> Self::HashCode
     where T: ?Sized + Hash,
           H: Hasher + Default,
           Self: Default,
     {
         Self::default()
             .get_or_init(|| {
+                #[cfg(feature = "stats")]
+                stats::record_computation();
                 let mut hasher = H::default();
                 value.hash(&mut hasher);
-                hasher.finish()
+                let hash = hasher.finish();
+                #[cfg(feature = "debug-hasher")]
+                debug_hasher::assert_consistent::<T, H, ()>(value, &(), hash);
+                FromHash::from_hash(hash)
             })
     }
 }
 
-impl HashStorer for Cell<u64> {
-    fn clear(&mut self) {
-        self.set(0)
-    }
+macro_rules! impl_cell_storer {
+    ($($ty:ty),+ $(,)?) => {$(
+        impl HashStorer for Cell<$ty> {
+            type HashCode = $ty;
 
-    fn get(&self) -> Option<u64> {
-        let n = self.get();
-        if n == 0 { return None; }
-        Some(n)
-    }
+            fn clear(&mut self) {
+                self.set(0)
+            }
 
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64,
-    {
-        HashStorer::get(self)
-            .unwrap_or_else(|| {
-                let mut n = f();
-                if n == 0 { n = ZERO_MAPPED }
-                self.set(n);
-                n
-            })
-    }
+            fn get(&self) -> Option<$ty> {
+                let n = self.get();
+                if n == 0 { return None; }
+                Some(n)
+            }
+
+            fn set(&self, mut code: $ty) {
+                if code == 0 { code = FromHash::ZERO_MAPPED }
+                Cell::set(self, code)
+            }
+
+            fn get_or_init<F>(&self, f: F) -> $ty
+            where F: FnOnce() -> $ty,
+            {
+                HashStorer::get(self)
+                    .unwrap_or_else(|| {
+                        let mut n = f();
+                        if n == 0 { n = FromHash::ZERO_MAPPED }
+                        self.set(n);
+                        n
+                    })
+            }
+        }
+    )+};
 }
-impl HashStorer for AtomicU64 {
-    fn clear(&mut self) {
-        self.store(0, MOrd::Relaxed)
-    }
+impl_cell_storer!(u8, u16, u32, u64);
 
-    fn get(&self) -> Option<u64> {
-        let n = self.load(MOrd::Relaxed);
-        if n == 0 { return None; }
-        Some(n)
-    }
+macro_rules! impl_option_cell_storer {
+    ($($ty:ty),+ $(,)?) => {$(
+        /// Zero hash code is stored as is, no [`FromHash::ZERO_MAPPED`] sentinel,
+        /// at the cost of a flag doubling the size, e.g `Option<u32>` is 8 bytes,
+        /// see [`ExactAtomicU32`] for the atomic equivalent
+        impl HashStorer for Cell<Option<$ty>> {
+            type HashCode = $ty;
 
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64,
-    {
-        HashStorer::get(self)
-            .unwrap_or_else(|| {
-                let mut n = f();
-                if n == 0 { n = ZERO_MAPPED }
-                self.store(n, MOrd::Relaxed);
-                n
-            })
-    }
+            fn clear(&mut self) {
+                self.set(None)
+            }
+
+            fn get(&self) -> Option<$ty> {
+                self.get()
+            }
+
+            fn set(&self, code: $ty) {
+                Cell::set(self, Some(code))
+            }
+
+            fn get_or_init<F>(&self, f: F) -> $ty
+            where F: FnOnce() -> $ty,
+            {
+                HashStorer::get(self)
+                    .unwrap_or_else(|| {
+                        let n = f();
+                        Cell::set(self, Some(n));
+                        n
+                    })
+            }
+        }
+    )+};
+}
+impl_option_cell_storer!(u8, u16, u32);
+
+macro_rules! impl_atomic_storer {
+    ($($atomic:ty => $ty:ty),+ $(,)?) => {$(
+        impl HashStorer for $atomic {
+            type HashCode = $ty;
+
+            fn clear(&mut self) {
+                self.store(0, MOrd::Relaxed)
+            }
+
+            fn get(&self) -> Option<$ty> {
+                let n = self.load(MOrd::Relaxed);
+                if n == 0 { return None; }
+                Some(n)
+            }
+
+            fn set(&self, mut code: $ty) {
+                if code == 0 { code = FromHash::ZERO_MAPPED }
+                self.store(code, MOrd::Relaxed)
+            }
+
+            fn get_or_init<F>(&self, f: F) -> $ty
+            where F: FnOnce() -> $ty,
+            {
+                HashStorer::get(self)
+                    .unwrap_or_else(|| {
+                        let mut n = f();
+                        if n == 0 { n = FromHash::ZERO_MAPPED }
+                        self.store(n, MOrd::Relaxed);
+                        n
+                    })
+            }
+        }
+    )+};
 }
-impl HashStorer for NoneStorer {
+impl_atomic_storer! {
+    AtomicU8 => u8,
+    AtomicU16 => u16,
+    AtomicU32 => u32,
+    AtomicU64 => u64,
+}
+/// Model checked atomics, see `tests/loom.rs`
+#[cfg(loom)]
+impl_atomic_storer! {
+    loom::sync::atomic::AtomicU8 => u8,
+    loom::sync::atomic::AtomicU16 => u16,
+    loom::sync::atomic::AtomicU32 => u32,
+    loom::sync::atomic::AtomicU64 => u64,
+}
+
+impl<T: Hash + FromHash + Eq> HashStorer for NoneStorer<T> {
+    type HashCode = T;
+
+    const CACHES: bool = false;
+
     #[inline]
-    fn get(&self) -> Option<u64> {
+    fn get(&self) -> Option<T> {
         None
     }
 
+    #[inline]
+    fn set(&self, _code: T) { }
+
     #[inline]
     fn clear(&mut self) { }
 
     #[inline]
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64,
+    fn get_or_init<F>(&self, f: F) -> T
+    where F: FnOnce() -> T,
     {
         f()
     }
 }
+/// Shared storer, clones of a [`How`] share the hash cache
+///
+/// [`HashStorer::clear`] is clone-on-write: when the storer is shared,
+/// only this handle is detached to a new empty storer,
+/// other handles keep the shared cache unchanged
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::{cell::Cell, collections::hash_map::DefaultHasher, rc::Rc};
+/// let a: How<String, DefaultHasher, Rc<Cell<u64>>> = How::new("foo".into());
+/// let mut b = a.clone();
+/// How::make_hash(&a);
+/// assert!(How::is_hashed(&b));
+///
+/// b.push('!');
+/// assert!(! How::is_hashed(&b));
+/// assert!(How::is_hashed(&a));
+/// ```
 impl<T: HashStorer + Default> HashStorer for Rc<T> {
-    fn get(&self) -> Option<u64> {
+    type HashCode = T::HashCode;
+
+    const CACHES: bool = T::CACHES;
+
+    fn get(&self) -> Option<Self::HashCode> {
         <T as HashStorer>::get(&**self)
     }
 
+    fn set(&self, code: Self::HashCode) {
+        <T as HashStorer>::set(&**self, code)
+    }
+
     fn clear(&mut self) {
         Rc::get_mut(self)
             .map(T::clear)
@@ -135,25 +558,56 @@ impl<T: HashStorer + Default> HashStorer for Rc<T> {
             })
     }
 
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64,
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode,
     {
         <T as HashStorer>::get_or_init(&**self, f)
     }
 
-    fn hash_one<T1, H>(value: &T1) -> u64
+    fn hash_one<T1, H>(value: &T1) -> Self::HashCode
     where T1: ?Sized + Hash,
           H: Hasher + Default,
           Self: Default,
     {
         T::hash_one::<T1, H>(value)
     }
+
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        T::write_code(code, state)
+    }
 }
+/// Shared storer, clones of a [`How`] share the hash cache
+///
+/// [`HashStorer::clear`] is clone-on-write: when the storer is shared,
+/// only this handle is detached to a new empty storer,
+/// other handles keep the shared cache unchanged
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::{cell::Cell, collections::hash_map::DefaultHasher, sync::Arc};
+/// let a: How<String, DefaultHasher, Arc<Cell<u64>>> = How::new("foo".into());
+/// let mut b = a.clone();
+/// How::make_hash(&a);
+/// assert!(How::is_hashed(&b));
+///
+/// b.push('!');
+/// assert!(! How::is_hashed(&b));
+/// assert!(How::is_hashed(&a));
+/// ```
 impl<T: HashStorer + Default> HashStorer for Arc<T> {
-    fn get(&self) -> Option<u64> {
+    type HashCode = T::HashCode;
+
+    const CACHES: bool = T::CACHES;
+
+    fn get(&self) -> Option<Self::HashCode> {
         <T as HashStorer>::get(&**self)
     }
 
+    fn set(&self, code: Self::HashCode) {
+        <T as HashStorer>::set(&**self, code)
+    }
+
     fn clear(&mut self) {
         Arc::get_mut(self)
             .map(T::clear)
@@ -162,19 +616,65 @@ impl<T: HashStorer + Default> HashStorer for Arc<T> {
             })
     }
 
-    fn get_or_init<F>(&self, f: F) -> u64
-    where F: FnOnce() -> u64,
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode,
     {
         <T as HashStorer>::get_or_init(&**self, f)
     }
 
-    fn hash_one<T1, H>(value: &T1) -> u64
+    fn hash_one<T1, H>(value: &T1) -> Self::HashCode
     where T1: ?Sized + Hash,
           H: Hasher + Default,
           Self: Default,
     {
         T::hash_one::<T1, H>(value)
     }
+
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        T::write_code(code, state)
+    }
+}
+
+/// Uniformly get cached hash code from wrappers, like [`How`] and [`Borrowed`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, Borrowed, MaybeHashed};
+/// fn count_hashed<T: MaybeHashed + ?Sized>(values: &[&T]) -> usize {
+///     values.iter().filter(|x| x.cached_code().is_some()).count()
+/// }
+/// let (a, b) = (How::new_default("a"), How::new_default("b"));
+/// How::make_hash(&a);
+/// assert_eq!(count_hashed(&[&a, &b]), 1);
+/// assert_eq!(count_hashed::<Borrowed<str>>(&[Borrowed::make_ref("a")]), 0);
+/// ```
+///
+/// [`How`]: crate::How
+/// [`Borrowed`]: crate::Borrowed
+pub trait MaybeHashed {
+    /// Get cached hash code, widening to `u64`
+    fn cached_code(&self) -> Option<u64>;
+}
+impl<T: ?Sized, H, S: HashStorer> MaybeHashed for How<T, H, S> {
+    fn cached_code(&self) -> Option<u64> {
+        How::hash_code_u64(self)
+    }
+}
+impl<T: ?Sized, H, S> MaybeHashed for Borrowed<T, H, S> {
+    /// Always `None`, [`Borrowed`] never caches
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    fn cached_code(&self) -> Option<u64> {
+        None
+    }
+}
+impl<T: ?Sized, H> MaybeHashed for FrozenHow<T, H> {
+    /// Always `Some`, [`FrozenHow`] is computed in constructor
+    ///
+    /// [`FrozenHow`]: crate::FrozenHow
+    fn cached_code(&self) -> Option<u64> {
+        Some(FrozenHow::hash_code(self))
+    }
 }
 
 /// A wrapper for storing hash results to avoid running costly hash functions
@@ -199,9 +699,49 @@ impl<T: HashStorer + Default> HashStorer for Arc<T> {
 /// Due to the inability of the stored hashcode to replicate the action of `T::hash,`
 /// it is not possible to implement [`Borrow<T>`]
 ///
+/// [`Ord`] is only compare values, so `How` can also be used as [`BTreeMap`] key,
+/// and lookup by [`Borrowed`], it has the same ordering as the value
+///
+/// ```
+/// # use hash_on_write::{How, Borrowed};
+/// # use std::collections::BTreeMap;
+/// let mut map: BTreeMap<How<String>, i32> = BTreeMap::new();
+/// map.insert("a".to_owned().into(), 1);
+/// assert_eq!(map.get(Borrowed::make_ref("a")), Some(&1));
+/// ```
+///
+/// [`Deref`] target is `T` rather than [`Borrowed`], a `How → Borrowed → T` chain
+/// would make `*how` a `Borrowed` and `&*how` no longer a `&T`,
+/// breaking existing code and [`Deref`] coercions to `&T`.
+/// For APIs parameterised on `Borrowed`, use [`AsRef<Borrowed<T, H, S>>`] or [`Borrow`]
+///
+/// ```
+/// # use hash_on_write::{How, Borrowed};
+/// fn probe<K: AsRef<Borrowed<String>>>(key: &K) -> &Borrowed<String> {
+///     key.as_ref()
+/// }
+/// let x: How<String> = How::new("foo".to_owned());
+/// let y: Borrowed<String> = Borrowed::new("foo".to_owned());
+/// assert_eq!(probe(&x), probe(&y));
+/// ```
+///
+/// No hasher value is stored, so auto traits ([`Send`], [`Sync`], [`Unpin`], etc)
+/// only depend on `T` and `S`, `How` is covariant in `H` like in `T`
+///
+/// ```
+/// # use hash_on_write::How;
+/// # use std::{collections::hash_map::DefaultHasher, hash::Hasher, rc::Rc, sync::atomic::AtomicU64};
+/// #[derive(Default)]
+/// struct NonSendHasher(DefaultHasher, Rc<()>);
+/// fn assert_send_sync<T: Send + Sync>() {}
+/// assert_send_sync::<How<String, NonSendHasher, AtomicU64>>();
+/// ```
+///
 /// [`Borrow<T>`]: core::borrow::Borrow
+/// [`BTreeMap`]: std::collections::BTreeMap
+/// [`AsRef<Borrowed<T, H, S>>`]: AsRef
 pub struct How<T: ?Sized, H = DefaultHasher, S = Cell<u64>> {
-    _hasher: PhantomData<H>,
+    _hasher: PhantomData<fn() -> H>,
     hashcode: S,
     value: T,
 }
@@ -223,6 +763,16 @@ impl<T: ?Sized, H, S: HashStorer> AsMut<T> for How<T, H, S> {
         How::make_mut(self)
     }
 }
+/// Hash-transparent view as [`Borrowed`], for APIs parameterised on `Borrowed`
+///
+/// [`Deref`] target stays `T`, see [`How`] docs
+///
+/// [`Borrowed`]: crate::Borrowed
+impl<T: ?Sized, H, S> AsRef<Borrowed<T, H, S>> for How<T, H, S> {
+    fn as_ref(&self) -> &Borrowed<T, H, S> {
+        Borrowed::make_ref(&self.value)
+    }
+}
 impl<T: ?Sized, H, S> AsRef<Self> for How<T, H, S> {
     fn as_ref(&self) -> &Self {
         self
@@ -233,6 +783,32 @@ impl<T: ?Sized, H, S> AsMut<Self> for How<T, H, S> {
         self
     }
 }
+/// Lookup by [`Borrowed`] relies on the [`Borrow`] contract of `T`:
+/// `T` and `Q` must hash and compare the same,
+/// because the cached code is computed from `T`, and the probe from `Q`.
+/// `String: Borrow<str>`, `Vec<T>: Borrow<[T]>`, `Box<T>: Borrow<T>` etc uphold it,
+/// a `Borrow` impl breaking it is a logic error,
+/// lookups may fail but it is never memory unsafe, same as std [`HashMap`]
+///
+/// ```
+/// # use hash_on_write::{Borrowed, How};
+/// # use std::{borrow::Borrow, collections::hash_map::RandomState, hash::{BuildHasher, Hash, Hasher}};
+/// #[derive(PartialEq, Eq)]
+/// struct Tagged(String, u32);
+/// impl Hash for Tagged {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         self.0.hash(state);
+///         self.1.hash(state); // not hashed by `str`, breaks the contract
+///     }
+/// }
+/// impl Borrow<str> for Tagged {
+///     fn borrow(&self) -> &str { &self.0 }
+/// }
+/// let key = How::new_default(Tagged("foo".into(), 1));
+/// let state = RandomState::new();
+/// // so a lookup by `Borrowed` may miss the key
+/// assert_ne!(state.hash_one(&key), state.hash_one(Borrowed::<str>::make_ref("foo")));
+/// ```
 impl<T, Q, H, S> Borrow<Borrowed<Q, H, S>> for How<T, H, S>
 where T: ?Sized + Borrow<Q>,
       Q: ?Sized,
@@ -257,7 +833,7 @@ impl<T: ?Sized + Debug, H, S: Debug> Debug for How<T, H, S> {
             .finish()
     }
 }
-impl<T: ?Sized + Clone, H, S: Clone> Clone for How<T, H, S> {
+impl<T: Clone, H, S: Clone> Clone for How<T, H, S> {
     fn clone(&self) -> Self {
         Self {
             _hasher: PhantomData,
@@ -266,18 +842,50 @@ impl<T: ?Sized + Clone, H, S: Clone> Clone for How<T, H, S> {
         }
     }
 }
-impl<T: ?Sized + Eq, H, S: HashStorer> Eq for How<T, H, S> { }
-impl<T: ?Sized + Ord, H, S: HashStorer> Ord for How<T, H, S> {
+impl<T: ?Sized + Eq, H: BuildInnerHasher<S>, S: HashStorer> Eq for How<T, H, S> { }
+impl<T: ?Sized + Ord, H: BuildInnerHasher<S>, S: HashStorer> Ord for How<T, H, S> {
     fn cmp(&self, other: &Self) -> Ordering {
         self.value.cmp(&other.value)
     }
 }
-impl<T: ?Sized + PartialEq, H, S: HashStorer> PartialEq for How<T, H, S> {
+/// Compare cached hash codes first if both are present, then compare values
+///
+/// With the `strict-eq` feature, in debug builds,
+/// equal hash codes with unequal values panic with "hash collision detected",
+/// helps catch hasher quality problems during development.
+/// No extra work in release builds
+///
+/// Hash collisions are legal, e.g [`DynKey`] of different types, only enable it during development
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::{cell::Cell, collections::hash_map::DefaultHasher, panic::AssertUnwindSafe};
+/// type NHow = How<u32, DefaultHasher, Cell<u8>>;
+/// let a = NHow::new(0);
+/// let b = (1..).map(NHow::new)
+///     .find(|b| How::make_hash(b) == How::make_hash(&a))
+///     .unwrap();
+/// let result = std::panic::catch_unwind(AssertUnwindSafe(|| a == b));
+/// let strict = cfg!(all(feature = "strict-eq", debug_assertions));
+/// assert_eq!(result.is_err(), strict);
+/// ```
+impl<T: ?Sized + PartialEq, H: BuildInnerHasher<S>, S: HashStorer> PartialEq for How<T, H, S> {
     fn eq(&self, other: &Self) -> bool {
-        self.hashcode.get()
+        let codes = self.hashcode.get()
             .zip(other.hashcode.get())
-            .map_or(true, |(a, b)| a == b)
-            && self.value == other.value
+            .filter(|_| H::COMPARABLE);
+        if codes.as_ref().is_some_and(|(a, b)| a != b) {
+            return false;
+        }
+        let eq = self.value == other.value;
+        #[cfg(all(feature = "strict-eq", debug_assertions))]
+        assert!(codes.is_none() || eq, "hash collision detected");
+        #[cfg(feature = "collisions")]
+        if codes.is_some() && !eq {
+            collisions::record_collision()
+        }
+        eq
     }
 }
 impl<T: ?Sized + PartialEq, H, S> PartialEq<T> for How<T, H, S> {
@@ -285,7 +893,7 @@ impl<T: ?Sized + PartialEq, H, S> PartialEq<T> for How<T, H, S> {
         **self == *other
     }
 }
-impl<T: ?Sized + PartialOrd, H, S: HashStorer> PartialOrd for How<T, H, S> {
+impl<T: ?Sized + PartialOrd, H: BuildInnerHasher<S>, S: HashStorer> PartialOrd for How<T, H, S> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.value.partial_cmp(&other.value)
     }
@@ -304,7 +912,7 @@ impl<T: ?Sized, H, S> Deref for How<T, H, S> {
 }
 impl<T, H, S> DerefMut for How<T, H, S>
 where T: ?Sized + Hash,
-      H: Hasher + Default,
+      H: BuildInnerHasher<S>,
       S: HashStorer,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
@@ -313,15 +921,45 @@ where T: ?Sized + Hash,
 }
 impl<T, IH, S> Hash for How<T, IH, S>
 where T: ?Sized + Hash,
-      IH: Hasher + Default,
+      IH: BuildInnerHasher<S>,
       S: HashStorer,
 {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        Self::make_hash(self)
-            .hash(state)
+        Self::hash_into(self, state)
     }
 }
+/// Wrap the value, the hash cache is empty,
+/// e.g `From<HashMap<K, V>>` for `How<HashMap<K, V>>`
+///
+/// The hash of the whole value is cached, so a large collection is iterated only once,
+/// then every lookup and rehash reuses the code until mutated.
+/// [`HashMap`] and [`HashSet`] do not implement [`Hash`], so such a [`How`] can not be hashed,
+/// use [`BTreeMap`] or [`BTreeSet`] for hashable collections,
+/// they hash in key order, independent of insertion order
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::collections::{BTreeMap, HashMap};
+/// let map: How<HashMap<i32, i32>> = HashMap::from([(1, 2)]).into();
+/// assert_eq!(map[&1], 2);
+///
+/// let map: How<BTreeMap<i32, i32>> = (0..1000).map(|i| (i, i)).collect::<BTreeMap<_, _>>().into();
+/// let code = How::make_hash(&map);
+/// assert!(How::is_hashed(&map));
+/// assert_eq!(How::make_hash(&map), code);
+/// ```
+///
+/// ```compile_fail
+/// # use hash_on_write::How;
+/// # use std::collections::HashMap;
+/// let map: How<HashMap<i32, i32>> = HashMap::new().into();
+/// How::make_hash(&map);
+/// ```
+///
+/// [`BTreeMap`]: std::collections::BTreeMap
+/// [`BTreeSet`]: std::collections::BTreeSet
 impl<T, H, S> From<T> for How<T, H, S>
 where H: Hasher + Default,
       S: HashStorer + Default,
@@ -331,65 +969,880 @@ where H: Hasher + Default,
         Self::new(value)
     }
 }
+/// Collect into set, the hash cache is empty
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::collections::HashSet;
+/// let x: How<HashSet<i32>> = vec![1, 2, 1].into();
+/// assert_eq!(x.len(), 2);
+/// ```
+impl<T, B, H, S> From<Vec<T>> for How<HashSet<T, B>, H, S>
+where T: Hash + Eq,
+      B: BuildHasher + Default,
+      S: HashStorer + Default,
+{
+    fn from(value: Vec<T>) -> Self {
+        Self::new(value.into_iter().collect())
+    }
+}
+/// Collect into map, later values overwrite earlier ones, the hash cache is empty
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use std::collections::HashMap;
+/// let x: How<HashMap<&str, i32>> = vec![("a", 1), ("a", 2)].into();
+/// assert_eq!(x["a"], 2);
+/// ```
+impl<K, V, B, H, S> From<Vec<(K, V)>> for How<HashMap<K, V, B>, H, S>
+where K: Hash + Eq,
+      B: BuildHasher + Default,
+      S: HashStorer + Default,
+{
+    fn from(value: Vec<(K, V)>) -> Self {
+        Self::new(value.into_iter().collect())
+    }
+}
+/// Clear the hash cache before writing
+///
+/// [`write_fmt`] clears once at start, then writes into the inner [`String`] directly,
+/// so `write!(how, ...)` clear exactly once, however many pieces are written
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// use std::fmt::Write;
+///
+/// let mut x = How::new_default(String::from("foo"));
+/// How::make_hash(&x);
+/// write!(x, "{}-{}", 1, 2).unwrap();
+/// assert!(! How::is_hashed(&x));
+/// assert_eq!(*x, "foo1-2");
+/// ```
+///
+/// [`write_fmt`]: fmt::Write::write_fmt
+impl<H, S: HashStorer> fmt::Write for How<String, H, S> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Self::make_mut(self).push_str(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        Self::make_mut(self).push(c);
+        Ok(())
+    }
+
+    fn write_fmt(&mut self, args: fmt::Arguments<'_>) -> fmt::Result {
+        fmt::Write::write_fmt(Self::make_mut(self), args)
+    }
+}
+/// Reading only advances the cursor position, does not clear the hash cache
+impl<T, H, S> io::Read for How<Cursor<T>, H, S>
+where Cursor<T>: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.value.read(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.value.read_exact(buf)
+    }
+}
+/// Clear the hash cache before writing
+impl<T, H, S: HashStorer> io::Write for How<Cursor<T>, H, S>
+where Cursor<T>: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Self::make_mut(self).write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        Self::make_mut(self).write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.value.flush()
+    }
+}
 impl<T> How<T> {
     /// new, but use [`DefaultHasher`]
     ///
+    /// Alias of [`How::with_value`]
+    ///
     /// [`DefaultHasher`]: std::collections::hash_map::DefaultHasher
     pub fn new_default(value: T) -> Self {
+        Self::with_value(value)
+    }
+}
+impl<T, H, S: Default> How<T, H, S> {
+    /// New a wrapped value, usable for any hasher and storer
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{cell::Cell, collections::hash_map::DefaultHasher};
+    /// let x: How<_, DefaultHasher, Cell<u64>> = How::with_value("foo");
+    /// assert!(! How::is_hashed(&x));
+    /// ```
+    #[inline]
+    pub fn with_value(value: T) -> Self {
         How {
             _hasher: PhantomData,
             hashcode: Default::default(),
             value,
         }
     }
-}
-impl<T, H, S: Default> How<T, H, S> {
+
     /// New a wrapped value
+    ///
+    /// Alias of [`How::with_value`]
     #[inline]
     pub fn new(value: T) -> Self {
+        Self::with_value(value)
+    }
+}
+impl<T, H, S: HashStorer + Default> How<T, H, S> {
+    /// New a wrapped value with a trusted hash code,
+    /// zero is mapped like [`HashStorer::set`]
+    ///
+    /// Caller must make sure `code` is computed by `H` from `value`,
+    /// otherwise [`Hash`] and [`Borrowed`] lookup are inconsistent
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let code = How::make_hash(&How::new_default("foo"));
+    /// let x: How<&str> = How::new_with_code("foo", code);
+    /// assert_eq!(How::hash_code(&x), Some(code));
+    /// ```
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    pub fn new_with_code(value: T, code: S::HashCode) -> Self {
+        let how = Self::new(value);
+        how.hashcode.set(code);
+        how
+    }
+}
+impl<T, H, S: HashStorer> How<T, H, S> {
+    /// New a wrapped value use a given storer
+    ///
+    /// The storer is cleared before use
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{collections::hash_map::DefaultHasher, sync::{Arc, atomic::AtomicU64}};
+    /// let x: How<_, DefaultHasher, _> = How::new_in("foo", Arc::new(AtomicU64::new(0)));
+    /// assert!(! How::is_hashed(&x));
+    /// ```
+    pub fn new_in(value: T, mut storer: S) -> Self {
+        storer.clear();
         How {
             _hasher: PhantomData,
-            hashcode: Default::default(),
+            hashcode: storer,
             value,
         }
     }
 }
+impl<T, H, S: HashStorer> How<Vec<T>, H, S> {
+    /// New a empty [`Vec`] with capacity, use a given storer
+    pub fn with_capacity_and_storer(capacity: usize, storer: S) -> Self {
+        Self::new_in(Vec::with_capacity(capacity), storer)
+    }
+}
+impl<T, H, S: Default> How<Vec<T>, H, S> {
+    /// New a empty [`Vec`] with capacity
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x: How<Vec<i32>> = How::with_capacity(8);
+    /// assert!(x.capacity() >= 8);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(Vec::with_capacity(capacity))
+    }
+}
+impl<H, S: Default> How<String, H, S> {
+    /// New a [`String`] formatted from [`Display`](fmt::Display) value
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x: How<String> = How::from_display(12);
+    /// assert_eq!(*x, "12");
+    /// assert!(! How::is_hashed(&x));
+    /// ```
+    pub fn from_display<D: fmt::Display>(value: D) -> Self {
+        Self::new(value.to_string())
+    }
+}
+impl<T, H, S> How<Vec<T>, H, S> {
+    /// Get the inner slice, does not touch the hash cache
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default(vec![1, 2]);
+    /// assert_eq!(How::as_slice(&x), &[1, 2]);
+    /// ```
+    pub fn as_slice(this: &Self) -> &[T] {
+        this.value.as_slice()
+    }
+}
+impl<H, S> How<String, H, S> {
+    /// Get the inner str, does not touch the hash cache
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo".to_owned());
+    /// assert_eq!(How::as_str(&x), "foo");
+    /// ```
+    pub fn as_str(this: &Self) -> &str {
+        this.value.as_str()
+    }
+
+    /// Get [`Borrowed<str>`] for lookup, preserving `H` and `S`
+    ///
+    /// Same as the general [`Borrow`] route `How::borrow(this)`,
+    /// but without annotating the borrowed type
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::collections::HashSet;
+    /// let set: HashSet<How<String>> = HashSet::from([How::new("foo".to_owned())]);
+    /// let key = How::new("foo".to_owned());
+    /// assert!(set.contains(How::probe_str(&key)));
+    /// ```
+    ///
+    /// [`Borrowed<str>`]: crate::Borrowed
+    pub fn probe_str(this: &Self) -> &Borrowed<str, H, S> {
+        Borrowed::make_ref(this.value.as_str())
+    }
+}
 
 impl<T, H, S> How<T, H, S> {
     /// Consume `self` into wrapped value
     pub fn into_inner(this: Self) -> T {
         this.value
     }
+
+    /// Take the wrapped value out of an [`Arc`] if it is the sole owner,
+    /// otherwise give the [`Arc`] back
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::sync::Arc;
+    /// let a = Arc::new(How::new_default("foo".to_owned()));
+    /// let b = a.clone();
+    /// let a = How::try_unwrap_arc(a).unwrap_err();
+    /// drop(b);
+    /// assert_eq!(How::try_unwrap_arc(a).unwrap(), "foo");
+    /// ```
+    pub fn try_unwrap_arc(this: Arc<Self>) -> Result<T, Arc<Self>> {
+        Arc::try_unwrap(this).map(Self::into_inner)
+    }
+
+    /// Take the wrapped value out of an [`Rc`] if it is the sole owner,
+    /// otherwise give the [`Rc`] back
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::rc::Rc;
+    /// let a = Rc::new(How::new_default("foo".to_owned()));
+    /// let b = a.clone();
+    /// let a = How::try_unwrap_rc(a).unwrap_err();
+    /// drop(b);
+    /// assert_eq!(How::try_unwrap_rc(a).unwrap(), "foo");
+    /// ```
+    pub fn try_unwrap_rc(this: Rc<Self>) -> Result<T, Rc<Self>> {
+        Rc::try_unwrap(this).map(Self::into_inner)
+    }
+}
+impl<T: Capacity, H, S> How<T, H, S> {
+    /// Get the capacity of the value, does not touch the hash cache
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default(String::with_capacity(16));
+    /// assert!(How::capacity(&x) >= 16);
+    /// ```
+    pub fn capacity(this: &Self) -> usize {
+        this.value.capacity()
+    }
+}
+impl<T: CacheNeutralMut, H, S> How<T, H, S> {
+    /// Mutate the value without clearing the hash cache
+    ///
+    /// `f` must not change the hash relevant content,
+    /// e.g only calls [`CacheNeutralMut`] methods or other capacity operations,
+    /// otherwise the cached hash code is stale, a logic error like a broken [`Hash`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::{CacheNeutralMut, How};
+    /// let mut x = How::new_default(vec![1, 2]);
+    /// How::make_hash(&x);
+    /// let cap = How::neutral_mut(&mut x, |v| {
+    ///     v.reserve(8);
+    ///     v.capacity()
+    /// });
+    /// assert!(How::is_hashed(&x));
+    /// assert!(cap >= 10);
+    /// ```
+    pub fn neutral_mut<R>(this: &mut Self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut this.value)
+    }
+
+    /// Shrink the capacity, does not touch the hash cache,
+    /// the content and thus the hash are unchanged
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let mut x = How::new_default(String::with_capacity(16));
+    /// How::make_mut(&mut x).push_str("foo");
+    /// How::make_hash(&x);
+    /// How::shrink_to_fit(&mut x);
+    /// assert!(How::is_hashed(&x));
+    /// assert!(x.capacity() < 16);
+    /// ```
+    pub fn shrink_to_fit(this: &mut Self) {
+        Self::neutral_mut(this, T::shrink_to_fit)
+    }
+
+    /// Reserve capacity, does not touch the hash cache,
+    /// unlike `How::make_mut(this).reserve(additional)`
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let mut x = How::new_default(vec![1, 2]);
+    /// How::make_hash(&x);
+    /// How::reserve(&mut x, 16);
+    /// assert!(How::is_hashed(&x));
+    /// assert!(x.capacity() >= 18);
+    /// ```
+    pub fn reserve(this: &mut Self, additional: usize) {
+        Self::neutral_mut(this, |value| value.reserve(additional))
+    }
+}
+impl<T, H, S: HashStorer> How<T, H, S> {
+    /// Convert to other storer type,
+    /// the cached hash code is kept if hash code types are the same
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{cell::Cell, collections::hash_map::DefaultHasher, sync::atomic::{AtomicU64, AtomicU32}};
+    /// let x: How<_, DefaultHasher, Cell<u64>> = How::new("foo");
+    /// let code = How::make_hash(&x);
+    ///
+    /// let y: How<_, DefaultHasher, AtomicU64> = How::convert_storer(x.clone());
+    /// assert_eq!(How::hash_code(&y), Some(code));
+    ///
+    /// let z: How<_, DefaultHasher, AtomicU32> = How::convert_storer(x);
+    /// assert!(! How::is_hashed(&z));
+    /// ```
+    pub fn convert_storer<S2>(this: Self) -> How<T, H, S2>
+    where S2: HashStorer + Default,
+          S::HashCode: TransferCode<S2::HashCode>,
+    {
+        let code = this.hashcode.get().and_then(TransferCode::transfer);
+        let new = How::<T, H, S2>::new(this.value);
+        if let Some(code) = code {
+            new.hashcode.set(code);
+        }
+        new
+    }
+}
+impl<T: ?Sized, H, S> How<T, H, S> {
+    /// Get the hash code storer
+    ///
+    /// Low-level, mainly useful for inspecting shared storer, e.g `Arc<AtomicU64>`
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{collections::hash_map::DefaultHasher, sync::{Arc, atomic::AtomicU64}};
+    /// let a: How<_, DefaultHasher, Arc<AtomicU64>> = How::new("foo");
+    /// let b = a.clone();
+    /// assert!(Arc::ptr_eq(How::storer(&a), How::storer(&b)));
+    /// ```
+    pub fn storer(this: &Self) -> &S {
+        &this.hashcode
+    }
+
+    /// Get the address of the wrapped value, never touch the hash cache
+    ///
+    /// Useful for keying identity maps beside value lookup
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo".to_owned());
+    /// let y = x.clone();
+    /// assert_eq!(How::as_ptr(&x), &*x as *const String);
+    /// assert_ne!(How::as_ptr(&x), How::as_ptr(&y));
+    /// assert!(! How::is_hashed(&x));
+    /// ```
+    pub fn as_ptr(this: &Self) -> *const T {
+        &this.value
+    }
+
+    /// Borrow as [`Borrowed`] in [`Cow`], use [`Cow::into_owned`] to upgrade into [`How`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::{How, Borrowed};
+    /// # use std::{borrow::Cow, collections::HashMap};
+    /// let mut counts: HashMap<How<String>, i32> = HashMap::new();
+    /// let x: How<String> = How::new("foo".to_owned());
+    ///
+    /// for _ in 0..2 {
+    ///     let key: Cow<Borrowed<str>> = How::borrow_cow(&x);
+    ///     match counts.get_mut(&*key) {
+    ///         Some(count) => *count += 1,
+    ///         None => { counts.insert(key.into_owned(), 1); },
+    ///     }
+    /// }
+    /// assert_eq!(counts[Borrowed::make_ref("foo")], 2);
+    /// ```
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    pub fn borrow_cow<Q>(this: &Self) -> Cow<'_, Borrowed<Q, H, S>>
+    where Q: ?Sized,
+          T: Borrow<Q>,
+          Borrowed<Q, H, S>: ToOwned,
+    {
+        Cow::Borrowed(this.borrow())
+    }
+
+    /// Read the value by a shared reference, never touch the hash cache
+    ///
+    /// Same as `f(&*this)`, but makes read-only intent explicit at call site,
+    /// see [`How::inspect_mut`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo".to_owned());
+    /// How::make_hash(&x);
+    /// assert_eq!(How::inspect(&x, |s| s.len()), 3);
+    /// assert!(How::is_hashed(&x));
+    /// ```
+    pub fn inspect<R, F>(this: &Self, f: F) -> R
+    where F: FnOnce(&T) -> R,
+    {
+        f(&this.value)
+    }
+
+    /// Write the value by a mutable reference **without** clearing the hash cache
+    ///
+    /// The caller promises the mutation does not affect [`Hash`] of the value,
+    /// e.g only fields ignored by [`Hash`] are changed.
+    /// Breaking the promise is not unsafe, but lookups may then fail,
+    /// use [`How::inspect_mut`] when in doubt
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::hash::{Hash, Hasher};
+    /// struct Key { name: String, hits: u32 }
+    /// impl Hash for Key {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         self.name.hash(state)
+    ///     }
+    /// }
+    /// let mut x = How::new_default(Key { name: "foo".into(), hits: 0 });
+    /// How::make_hash(&x);
+    /// How::retain_cache(&mut x, |key| key.hits += 1);
+    /// assert!(How::is_hashed(&x));
+    /// assert_eq!(x.hits, 1);
+    /// ```
+    pub fn retain_cache<R, F>(this: &mut Self, f: F) -> R
+    where F: FnOnce(&mut T) -> R,
+    {
+        f(&mut this.value)
+    }
+
+    /// Compare values with a [`How`] of another hasher or storer, never touch hash caches
+    ///
+    /// Hash codes of different hashers are not comparable,
+    /// so no pre-check like [`PartialEq`] of same types,
+    /// a blanket `PartialEq<How<T, H2, S2>>` would overlap it,
+    /// also usable on same types, e.g when suspecting a hash collision
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{collections::hash_map::DefaultHasher, sync::atomic::AtomicU64};
+    /// let a: How<String> = How::new("foo".to_owned());
+    /// let b: How<String, DefaultHasher, AtomicU64> = How::new("foo".to_owned());
+    /// How::make_hash(&a);
+    /// assert!(How::eq_value(&a, &b));
+    /// assert!(! How::is_hashed(&b));
+    /// ```
+    pub fn eq_value<H2, S2>(this: &Self, other: &How<T, H2, S2>) -> bool
+    where T: PartialEq,
+    {
+        this.value == other.value
+    }
 }
 impl<T: ?Sized, H, S: HashStorer> How<T, H, S> {
     /// Get mutable and clear hash cache
     pub fn make_mut(this: &mut Self) -> &mut T {
+        #[cfg(feature = "stats")]
+        stats::record_invalidation();
+        #[cfg(feature = "tracing")]
+        trace::record_invalidation::<T>();
         this.hashcode.clear();
         &mut this.value
     }
 
+    /// Clear hash cache and write the value by a mutable reference,
+    /// like `f(How::make_mut(this))`, see [`How::inspect`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let mut x = How::new_default("foo".to_owned());
+    /// How::make_hash(&x);
+    /// How::inspect_mut(&mut x, |s| s.push('!'));
+    /// assert!(! How::is_hashed(&x));
+    /// assert_eq!(*x, "foo!");
+    /// ```
+    pub fn inspect_mut<R, F>(this: &mut Self, f: F) -> R
+    where F: FnOnce(&mut T) -> R,
+    {
+        f(Self::make_mut(this))
+    }
+
     /// Get hash cache status
-    pub fn hash_code(this: &Self) -> Option<u64> {
+    pub fn hash_code(this: &Self) -> Option<S::HashCode> {
         this.hashcode.get()
     }
 
+    /// Replace cached hash code by `f`, no-op if not cached
+    ///
+    /// Useful for combining hash codes, e.g XOR,
+    /// note that hashing not match [`Borrowed`] after mapped
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo");
+    /// How::map_hash_code(&x, |code| code ^ 1);
+    /// assert!(! How::is_hashed(&x));
+    ///
+    /// let code = How::make_hash(&x);
+    /// How::map_hash_code(&x, |code| code ^ 1);
+    /// assert_eq!(How::hash_code(&x), Some(code ^ 1));
+    /// ```
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    pub fn map_hash_code<F>(this: &Self, f: F)
+    where F: FnOnce(S::HashCode) -> S::HashCode,
+    {
+        if let Some(code) = this.hashcode.get() {
+            this.hashcode.set(f(code))
+        }
+    }
+
+    /// Get cached hash code, or compute it by `f` and store,
+    /// bypassing the inner hasher `H`
+    ///
+    /// Caller must keep `f` consistent with `H`,
+    /// otherwise [`Hash`] and [`Borrowed`] lookup are inconsistent
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo");
+    /// assert_eq!(How::or_compute(&x, |s| s.len() as u64), 3);
+    /// assert_eq!(How::or_compute(&x, |_| unreachable!()), 3);
+    /// assert_eq!(How::hash_code(&x), Some(3));
+    /// ```
+    ///
+    /// [`Borrowed`]: crate::Borrowed
+    pub fn or_compute<F>(this: &Self, f: F) -> S::HashCode
+    where F: FnOnce(&T) -> S::HashCode,
+    {
+        this.hashcode.get_or_init(|| f(&this.value))
+    }
+
+    /// Call `f` with cached hash code, no-op if not cached,
+    /// unlike [`How::make_hash`] never computes
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x = How::new_default("foo");
+    /// let mut hits = 0;
+    /// How::inspect_hash(&x, |_| hits += 1);
+    /// assert_eq!(hits, 0);
+    /// assert!(! How::is_hashed(&x));
+    ///
+    /// How::make_hash(&x);
+    /// How::inspect_hash(&x, |_| hits += 1);
+    /// assert_eq!(hits, 1);
+    /// ```
+    pub fn inspect_hash<F>(this: &Self, f: F)
+    where F: FnOnce(S::HashCode),
+    {
+        if let Some(code) = this.hashcode.get() {
+            f(code)
+        }
+    }
+
+    /// Get hash cache status, widening to `u64` by zero-extension
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::{cell::Cell, collections::hash_map::DefaultHasher};
+    /// let x: How<_, DefaultHasher, Cell<u16>> = How::new("foo");
+    /// let code = How::make_hash(&x);
+    /// assert_eq!(How::hash_code_u64(&x), Some(u64::from(code)));
+    /// ```
+    pub fn hash_code_u64(this: &Self) -> Option<u64> {
+        this.hashcode.get_u64()
+    }
+
+    /// Whether the storer actually caches hash code, see [`HashStorer::CACHES`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::{How, NoneStorer};
+    /// # use std::collections::hash_map::DefaultHasher;
+    /// const _: () = assert!(! How::<String, DefaultHasher, NoneStorer>::CACHES);
+    /// const _: () = assert!(How::<String>::CACHES);
+    /// ```
+    pub const CACHES: bool = S::CACHES;
+
     /// Get hash cache status is cached,
     /// like `How::hash_code(&value).is_some()`
+    ///
+    /// Always false and folded away when the storer does not [`CACHES`](How::CACHES)
     pub fn is_hashed(this: &Self) -> bool {
-        Self::hash_code(this).is_some()
+        S::CACHES && Self::hash_code(this).is_some()
+    }
+}
+impl<T: ?Sized, H: BuildInnerHasher<S>, S: HashStorer> How<T, H, S> {
+    /// Both hash codes are cached and different, implies `a != b` without comparing values
+    ///
+    /// `false` proves nothing, values must still be compared,
+    /// always `false` if the codes are not [`COMPARABLE`](BuildInnerHasher::COMPARABLE)
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let a = How::new_default("foo");
+    /// let b = How::new_default("bar");
+    /// assert!(! How::hash_differs(&a, &b));
+    ///
+    /// How::make_hash(&a);
+    /// How::make_hash(&b);
+    /// assert!(How::hash_differs(&a, &b));
+    /// assert!(! How::hash_differs(&a, &a));
+    /// ```
+    pub fn hash_differs(a: &Self, b: &Self) -> bool {
+        match (Self::hash_code(a), Self::hash_code(b)) {
+            (Some(a), Some(b)) => S::CACHES && H::COMPARABLE && a != b,
+            _ => false,
+        }
+    }
+}
+impl<T: ?Sized, H, C> How<T, H, NoneStorer<C>> {
+    /// Same as [`How::is_hashed`], but usable in const contexts,
+    /// always false for [`NoneStorer`]
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::{How, NoneStorer};
+    /// # use std::collections::hash_map::DefaultHasher;
+    /// const fn never_hashed<T>(x: &How<T, DefaultHasher, NoneStorer>) -> bool {
+    ///     ! How::is_hashed_const(x)
+    /// }
+    /// let x = How::new("foo");
+    /// How::make_hash(&x);
+    /// assert!(never_hashed(&x));
+    /// ```
+    pub const fn is_hashed_const(_this: &Self) -> bool {
+        false
     }
 }
 impl<T, H, S> How<T, H, S>
 where T: ?Sized + Hash,
-      H: Default + Hasher,
+      H: BuildInnerHasher<S>,
       S: HashStorer,
 {
     /// Get or init hash cache
-    pub fn make_hash(this: &Self) -> u64 {
-        this.hashcode.get_or_init(|| {
-            let mut inner_hasher = H::default();
+    ///
+    /// With the `debug-hasher` feature, in debug builds,
+    /// the value is hashed twice on computing, and panics if the two results differ,
+    /// catching inconsistent [`Hash`] impls and non-deterministic inner hashers
+    pub fn make_hash(this: &Self) -> S::HashCode {
+        #[cfg(any(feature = "stats", feature = "tracing"))]
+        let mut computed = false;
+        let code = this.hashcode.get_or_init(|| {
+            // a cached storer may re-run it for checking, e.g `CachePoisonDetector`
+            #[cfg(any(feature = "stats", feature = "tracing"))]
+            { computed = this.hashcode.get().is_none(); }
+            let mut inner_hasher = H::build_inner_hasher(&this.hashcode);
             this.value.hash(&mut inner_hasher);
-            inner_hasher.finish()
-        })
+            let hash = inner_hasher.finish();
+            #[cfg(feature = "debug-hasher")]
+            debug_hasher::assert_consistent::<T, H, S>(&this.value, &this.hashcode, hash);
+            FromHash::from_hash(hash)
+        });
+        #[cfg(feature = "stats")]
+        if computed {
+            stats::record_computation()
+        } else {
+            stats::record_hit()
+        }
+        #[cfg(feature = "tracing")]
+        if computed {
+            trace::record_computation::<T>(code.to_u64())
+        } else {
+            trace::record_hit::<T>(code.to_u64())
+        }
+        code
+    }
+
+    /// Get or init hash cache, and write the hash code into `state`,
+    /// same as [`Hash`] of [`How`], usable in manual [`Hash`] impls of containing types
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// # use std::hash::{Hash, Hasher};
+    /// struct Entry { key: How<String>, hits: u32 }
+    /// impl Hash for Entry {
+    ///     fn hash<H: Hasher>(&self, state: &mut H) {
+    ///         How::hash_into(&self.key, state)
+    ///     }
+    /// }
+    /// let entry = Entry { key: How::new("foo".to_owned()), hits: 0 };
+    /// let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    /// entry.hash(&mut hasher);
+    /// assert!(How::is_hashed(&entry.key));
+    /// ```
+    #[inline]
+    pub fn hash_into<H1: Hasher>(this: &Self, state: &mut H1) {
+        S::write_code(Self::make_hash(this), state)
+    }
+
+    /// Get or init hash cache, and compare to a previously captured hash code
+    ///
+    /// Dirty-tracking for values, `false` may be a hash collision of a changed value
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let mut x = How::new_default(vec![1, 2]);
+    /// let code = How::make_hash(&x);
+    /// assert!(! How::changed_since(&x, code));
+    ///
+    /// How::make_mut(&mut x).push(3);
+    /// assert!(How::changed_since(&x, code));
+    /// ```
+    pub fn changed_since(this: &Self, code: S::HashCode) -> bool {
+        Self::make_hash(this) != code
+    }
+
+    /// Compare hash codes first, then compare values on hash codes equal
+    ///
+    /// Hash codes of both are computed if not cached,
+    /// so it is a consistent total order, but **different** from [`Ord`],
+    /// only use it when an arbitrary order is acceptable, e.g grouping for dedup
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let mut values = Vec::from(["b", "a", "c", "a", "b"].map(How::new_default));
+    /// values.sort_by(How::cmp_by_code_then_value);
+    /// values.dedup();
+    /// assert_eq!(values.len(), 3);
+    /// ```
+    pub fn cmp_by_code_then_value(a: &Self, b: &Self) -> Ordering
+    where T: Ord,
+          S::HashCode: Ord,
+    {
+        Self::make_hash(a).cmp(&Self::make_hash(b))
+            .then_with(|| a.value.cmp(&b.value))
+    }
+}
+impl<'a, T: ?Sized, H, S: Default> How<&'a T, H, S> {
+    /// New a wrapped reference
+    ///
+    /// The hash cache is computed from the referent,
+    /// if the referent is mutated through interior mutability (e.g [`Cell`], [`RefCell`]),
+    /// the cache becomes stale, [`How::make_hash_checked`] can detect it in debug builds
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let s = String::from("foo");
+    /// let x: How<&str> = How::from_ref(s.as_str());
+    /// assert_eq!(How::make_hash(&x), How::make_hash(&How::new_default("foo")));
+    /// ```
+    ///
+    /// [`Cell`]: core::cell::Cell
+    /// [`RefCell`]: core::cell::RefCell
+    pub fn from_ref(value: &'a T) -> Self {
+        Self::new(value)
+    }
+}
+impl<T, H, S> How<&T, H, S>
+where T: ?Sized + Hash,
+      H: BuildInnerHasher<S>,
+      S: HashStorer,
+{
+    /// Like [`How::make_hash`],
+    /// but in debug builds recompute and assert the cached hash code is not stale
+    pub fn make_hash_checked(this: &Self) -> S::HashCode {
+        let code = Self::make_hash(this);
+        if cfg!(debug_assertions) {
+            let mut inner_hasher = H::build_inner_hasher(&this.hashcode);
+            this.value.hash(&mut inner_hasher);
+            let new_code = S::HashCode::from_hash(inner_hasher.finish());
+            debug_assert!(code_matches(code, new_code), "stale hash cache of referent");
+        }
+        code
+    }
+}
+impl<T, H, S> How<T, H, S>
+where T: Hash,
+      H: BuildInnerHasher<S>,
+      S: HashStorer,
+{
+    /// Group values by hash code, each value is hashed at most once
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let groups = How::group_by_code(["a", "b", "a"].map(How::new_default));
+    /// assert_eq!(groups.len(), 2);
+    /// assert!(groups.values().flatten().all(How::is_hashed));
+    /// ```
+    pub fn group_by_code<I>(iter: I) -> HashMap<S::HashCode, Vec<Self>>
+    where I: IntoIterator<Item = Self>,
+    {
+        let mut groups: HashMap<S::HashCode, Vec<Self>> = HashMap::new();
+        for value in iter {
+            groups.entry(Self::make_hash(&value))
+                .or_default()
+                .push(value);
+        }
+        groups
     }
 }
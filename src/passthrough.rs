@@ -0,0 +1,86 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// Outer hasher delivering the cached hash code of [`How`] verbatim as the map hash
+///
+/// Hashing a [`How`] writes only its hash code by [`Hasher::write_u64`] (or narrower),
+/// this hasher stores it directly, so lookups of cached keys do zero extra hash work.
+/// Other writes are mixed, but it is only intended for [`How`] and [`Borrowed`] keys
+///
+/// Best with `u64` hash codes, narrow codes leave the high bits zero,
+/// which some hash tables (e.g `hashbrown`) use for probing
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{Borrowed, How, PassthroughBuildHasher};
+/// # use std::{collections::HashMap, hash::BuildHasher};
+/// let mut map: HashMap<How<String>, i32, PassthroughBuildHasher> = HashMap::default();
+/// let key = How::new("foo".to_owned());
+/// let code = How::make_hash(&key);
+/// assert_eq!(PassthroughBuildHasher::default().hash_one(&key), code);
+///
+/// map.insert(key, 1);
+/// assert_eq!(map.get(Borrowed::make_ref("foo")), Some(&1));
+/// ```
+///
+/// [`How`]: crate::How
+/// [`Borrowed`]: crate::Borrowed
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PassthroughHasher {
+    hash: u64,
+    written: bool,
+}
+
+/// [`BuildHasher`](core::hash::BuildHasher) of [`PassthroughHasher`]
+pub type PassthroughBuildHasher = BuildHasherDefault<PassthroughHasher>;
+
+impl PassthroughHasher {
+    #[inline]
+    fn mix(&mut self, n: u64) {
+        self.written = true;
+        self.hash = (self.hash.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+}
+impl Hasher for PassthroughHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut buf = [0; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.mix(u64::from_le_bytes(buf));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write_u64(i.into())
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        if ! self.written {
+            self.written = true;
+            self.hash = i;
+        } else {
+            self.mix(i)
+        }
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64)
+    }
+}
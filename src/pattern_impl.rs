@@ -0,0 +1,75 @@
+use core::str::pattern::{Pattern, ReverseSearcher, Utf8Pattern};
+
+use crate::How;
+
+/// Use the wrapped value as pattern, the hash cache is unused
+impl<T: Pattern, H, S> Pattern for How<T, H, S> {
+    type Searcher<'a> = T::Searcher<'a>;
+
+    fn into_searcher(self, haystack: &str) -> Self::Searcher<'_> {
+        self.value.into_searcher(haystack)
+    }
+
+    fn is_contained_in(self, haystack: &str) -> bool {
+        self.value.is_contained_in(haystack)
+    }
+
+    fn is_prefix_of(self, haystack: &str) -> bool {
+        self.value.is_prefix_of(haystack)
+    }
+
+    fn is_suffix_of<'a>(self, haystack: &'a str) -> bool
+    where Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.value.is_suffix_of(haystack)
+    }
+
+    fn strip_prefix_of(self, haystack: &str) -> Option<&str> {
+        self.value.strip_prefix_of(haystack)
+    }
+
+    fn strip_suffix_of<'a>(self, haystack: &'a str) -> Option<&'a str>
+    where Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.value.strip_suffix_of(haystack)
+    }
+
+    fn as_utf8_pattern(&self) -> Option<Utf8Pattern<'_>> {
+        self.value.as_utf8_pattern()
+    }
+}
+
+/// Use the referenced wrapped value as pattern, e.g `&How<String>`
+impl<'b, T: ?Sized, H, S> Pattern for &'b How<T, H, S>
+where &'b T: Pattern,
+{
+    type Searcher<'a> = <&'b T as Pattern>::Searcher<'a>;
+
+    fn into_searcher(self, haystack: &str) -> Self::Searcher<'_> {
+        self.value.into_searcher(haystack)
+    }
+
+    fn is_contained_in(self, haystack: &str) -> bool {
+        self.value.is_contained_in(haystack)
+    }
+
+    fn is_prefix_of(self, haystack: &str) -> bool {
+        self.value.is_prefix_of(haystack)
+    }
+
+    fn is_suffix_of<'a>(self, haystack: &'a str) -> bool
+    where Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.value.is_suffix_of(haystack)
+    }
+
+    fn strip_prefix_of(self, haystack: &str) -> Option<&str> {
+        self.value.strip_prefix_of(haystack)
+    }
+
+    fn strip_suffix_of<'a>(self, haystack: &'a str) -> Option<&'a str>
+    where Self::Searcher<'a>: ReverseSearcher<'a>,
+    {
+        self.value.strip_suffix_of(haystack)
+    }
+}
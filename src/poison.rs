@@ -0,0 +1,69 @@
+use core::hash::Hasher;
+
+use crate::{code_matches, HashStorer};
+
+/// Debug wrapper of [`HashStorer`],
+/// verifies re-computed hash codes match cached ones
+///
+/// In debug builds, [`get_or_init`] on a cached storer re-runs the init func,
+/// and panics if the result is different,
+/// this catches values mutated without clearing the cache,
+/// e.g by unsafe code or interior mutability.
+/// No-op in release builds
+///
+/// # Examples
+/// ```should_panic
+/// # use hash_on_write::{CachePoisonDetector, How};
+/// # use std::{cell::{Cell, RefCell}, collections::hash_map::DefaultHasher, hash::{Hash, Hasher}};
+/// struct Key(RefCell<String>);
+/// impl Hash for Key {
+///     fn hash<H: Hasher>(&self, state: &mut H) {
+///         self.0.borrow().hash(state)
+///     }
+/// }
+/// let x: How<Key, DefaultHasher, CachePoisonDetector<Cell<u64>>>
+///     = How::new(Key(RefCell::new("foo".to_owned())));
+/// How::make_hash(&x);
+/// x.0.borrow_mut().push('!');
+/// How::make_hash(&x); // panic in debug builds
+/// # if ! cfg!(debug_assertions) { panic!() }
+/// ```
+///
+/// [`get_or_init`]: HashStorer::get_or_init
+#[derive(Debug, Default, Clone)]
+pub struct CachePoisonDetector<S>(pub S);
+
+impl<S: HashStorer> HashStorer for CachePoisonDetector<S> {
+    type HashCode = S::HashCode;
+
+    const CACHES: bool = S::CACHES;
+
+    fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    fn get(&self) -> Option<Self::HashCode> {
+        self.0.get()
+    }
+
+    fn set(&self, code: Self::HashCode) {
+        self.0.set(code)
+    }
+
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode,
+    {
+        let Some(code) = self.0.get() else {
+            return self.0.get_or_init(f);
+        };
+        if cfg!(debug_assertions) {
+            assert!(code_matches(code, f()),
+                "hash cache poisoned, the value is mutated without clearing the cache");
+        }
+        code
+    }
+
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        S::write_code(code, state)
+    }
+}
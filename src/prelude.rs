@@ -0,0 +1,23 @@
+//! Re-exports of commonly used items
+//!
+//! ```
+//! use hash_on_write::prelude::*;
+//!
+//! let x: SyncHow<String> = How::new("foo".to_owned());
+//! assert_eq!(How::make_hash(&x), How::make_hash(&How::new_default("foo".to_owned())));
+//! ```
+
+pub use crate::{
+    aliases::{LocalHow, SharedHow, SyncHow, UncachedHow},
+    Borrowed,
+    BuildInnerHasher,
+    CacheNeutralMut,
+    DynKey,
+    FromHash,
+    FrozenHow,
+    HashStorer,
+    How,
+    MaybeHashed,
+    NoneStorer,
+    TransferCode,
+};
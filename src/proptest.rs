@@ -0,0 +1,132 @@
+//! [proptest] strategies for [`How`], use by `features = ["proptest"]`
+//!
+//! Independently varies the value and whether the cache is warm,
+//! shrinking only the value and preserving the cache state
+//!
+//! # Examples
+//! ```
+//! # use hash_on_write::{How, proptest::how};
+//! # use proptest::prelude::*;
+//! # use std::hash::BuildHasher;
+//! proptest! {
+//!     fn hash_eq_consistent(a in how(".*"), b in how(".*")) {
+//!         let state = std::collections::hash_map::RandomState::new();
+//!         if a == b {
+//!             prop_assert_eq!(state.hash_one(&a), state.hash_one(&b));
+//!         }
+//!     }
+//! }
+//! hash_eq_consistent();
+//! ```
+//!
+//! [proptest]: ::proptest
+
+use ::proptest::{
+    strategy::{NewTree, Strategy, ValueTree},
+    test_runner::TestRunner,
+    bool::ANY,
+};
+use core::{
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+use std::collections::hash_map::DefaultHasher;
+
+use crate::{HashStorer, How};
+
+/// Strategy of [`How`] from a strategy of value, see [module docs](self)
+pub struct HowStrategy<St, H = DefaultHasher, S = Cell<u64>> {
+    _marker: PhantomData<fn() -> (H, S)>,
+    inner: St,
+}
+impl<St, H, S> HowStrategy<St, H, S> {
+    /// New a strategy from a strategy of value
+    pub fn new(inner: St) -> Self {
+        Self {
+            _marker: PhantomData,
+            inner,
+        }
+    }
+}
+impl<St: Debug, H, S> Debug for HowStrategy<St, H, S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HowStrategy")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+impl<St, H, S> Strategy for HowStrategy<St, H, S>
+where St: Strategy,
+      St::Value: Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default + Debug,
+{
+    type Tree = HowValueTree<St::Tree, H, S>;
+    type Value = How<St::Value, H, S>;
+
+    fn new_tree(&self, runner: &mut TestRunner) -> NewTree<Self> {
+        let hashed = ANY.new_tree(runner)?.current();
+        Ok(HowValueTree {
+            _marker: PhantomData,
+            inner: self.inner.new_tree(runner)?,
+            hashed,
+        })
+    }
+}
+
+/// Value tree of [`HowStrategy`], shrinking preserves the cache state
+pub struct HowValueTree<VT, H = DefaultHasher, S = Cell<u64>> {
+    _marker: PhantomData<fn() -> (H, S)>,
+    inner: VT,
+    hashed: bool,
+}
+impl<VT, H, S> HowValueTree<VT, H, S> {
+    /// Whether generated values are hashed
+    pub fn is_hashed(&self) -> bool {
+        self.hashed
+    }
+}
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Neither [`Send`] nor [`Sync`]
+    struct NonSendHasher(PhantomData<*const ()>);
+
+    assert_send_sync::<HowStrategy<(), NonSendHasher, std::rc::Rc<Cell<u64>>>>();
+    assert_send_sync::<HowValueTree<(), NonSendHasher, std::rc::Rc<Cell<u64>>>>();
+};
+impl<VT, H, S> ValueTree for HowValueTree<VT, H, S>
+where VT: ValueTree,
+      VT::Value: Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default + Debug,
+{
+    type Value = How<VT::Value, H, S>;
+
+    fn current(&self) -> Self::Value {
+        let how = How::new(self.inner.current());
+        if self.hashed {
+            How::make_hash(&how);
+        }
+        how
+    }
+
+    fn simplify(&mut self) -> bool {
+        self.inner.simplify()
+    }
+
+    fn complicate(&mut self) -> bool {
+        self.inner.complicate()
+    }
+}
+
+/// Strategy of [`How`] from a strategy of value, see [module docs](self)
+pub fn how<St>(inner: St) -> HowStrategy<St>
+where St: Strategy,
+      St::Value: Hash,
+{
+    HowStrategy::new(inner)
+}
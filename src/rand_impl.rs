@@ -0,0 +1,38 @@
+use core::hash::{Hash, Hasher};
+use rand::{
+    distributions::{Distribution, Standard},
+    Rng,
+};
+
+use crate::{HashStorer, How};
+
+/// Generate wrapped value, the hash cache is empty
+impl<T, H, S> Distribution<How<T, H, S>> for Standard
+where Standard: Distribution<T>,
+      S: Default,
+{
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> How<T, H, S> {
+        How::new(self.sample(rng))
+    }
+}
+
+impl<T, H, S> How<T, H, S>
+where T: Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      Standard: Distribution<T>,
+{
+    /// Generate a random wrapped value and warm the hash cache
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x: How<u64> = How::random_hashed(&mut rand::thread_rng());
+    /// assert!(How::is_hashed(&x));
+    /// ```
+    pub fn random_hashed<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let how = Self::new(rng.gen());
+        Self::make_hash(&how);
+        how
+    }
+}
@@ -0,0 +1,105 @@
+use core::{fmt, ops::Deref};
+use rkyv::{
+    bytecheck::CheckBytes,
+    munge::munge,
+    rancor::Fallible,
+    Archive, Archived, Deserialize, Place, Portable, Serialize,
+};
+
+use crate::{FromHash, HashStorer, How};
+
+/// Archived [`How`], stores `(code_or_zero, value)`
+///
+/// Cached hash codes survive into the archive and are restored on deserialize,
+/// **only sound with a stable inner hasher**, like [`serde_cached`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{ArchivedHow, How};
+/// let keys: Vec<How<String>> = vec![How::new("a".into()), How::new("b".into())];
+/// How::make_hash(&keys[0]);
+///
+/// let bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&keys).unwrap();
+/// let archived = rkyv::access::<rkyv::Archived<Vec<How<String>>>, rkyv::rancor::Error>(&bytes).unwrap();
+/// assert_eq!(archived[0].as_str(), "a");
+/// assert!(archived[0].hash_code().is_some());
+/// assert_eq!(archived[1].hash_code(), None);
+/// ```
+///
+/// [`serde_cached`]: crate::serde_cached
+#[derive(Portable, CheckBytes)]
+#[rkyv(crate = rkyv)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[repr(C)]
+pub struct ArchivedHow<T> {
+    hashcode: Archived<u64>,
+    value: T,
+}
+impl<T> ArchivedHow<T> {
+    /// Get archived hash code, zero-extended like [`How::hash_code_u64`]
+    pub fn hash_code(&self) -> Option<u64> {
+        Some(self.hashcode.to_native())
+            .filter(|&code| code != 0)
+    }
+
+    /// Get archived value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+impl<T> Deref for ArchivedHow<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+impl<T: fmt::Debug> fmt::Debug for ArchivedHow<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedHow")
+            .field("hashcode", &self.hash_code())
+            .field("value", &self.value)
+            .finish()
+    }
+}
+impl<T: PartialEq> PartialEq for ArchivedHow<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+impl<T: Eq> Eq for ArchivedHow<T> { }
+
+impl<T: Archive, H, S: HashStorer> Archive for How<T, H, S> {
+    type Archived = ArchivedHow<T::Archived>;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        munge!(let ArchivedHow { hashcode, value } = out);
+        How::hash_code_u64(self).unwrap_or(0).resolve((), hashcode);
+        self.value.resolve(resolver, value);
+    }
+}
+impl<T, H, S, Se> Serialize<Se> for How<T, H, S>
+where T: Serialize<Se>,
+      S: HashStorer,
+      Se: Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut Se) -> Result<Self::Resolver, Se::Error> {
+        self.value.serialize(serializer)
+    }
+}
+/// Restore the archived hash code into the cache
+impl<T, H, S, D> Deserialize<How<T, H, S>, D> for ArchivedHow<T::Archived>
+where T: Archive,
+      T::Archived: Deserialize<T, D>,
+      S: HashStorer + Default,
+      D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<How<T, H, S>, D::Error> {
+        let value = self.value.deserialize(deserializer)?;
+        Ok(match self.hash_code() {
+            Some(code) => How::new_with_code(value, FromHash::from_hash(code)),
+            None => How::new(value),
+        })
+    }
+}
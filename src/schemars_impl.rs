@@ -0,0 +1,25 @@
+use schemars::{JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+use crate::How;
+
+/// Transparent, the schema is indistinguishable from `T`
+impl<T, H, S> JsonSchema for How<T, H, S>
+where T: ?Sized + JsonSchema,
+{
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        T::json_schema(generator)
+    }
+}
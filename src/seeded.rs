@@ -0,0 +1,108 @@
+use core::{
+    cell::Cell,
+    hash::{BuildHasher, Hasher},
+};
+use std::collections::hash_map::RandomState;
+
+use crate::{BuildInnerHasher, HashStorer, How};
+
+/// Storer wrapper holding a [`BuildHasher`] instance, for stateful hashers e.g seeded
+///
+/// The hash code is only meaningful with the instance that built it, so both are stored together,
+/// use it with the [`StoredBuildHasher`] hasher, see [`SeededHow`]
+#[derive(Debug, Default, Clone)]
+pub struct Seeded<BH, S = Cell<u64>> {
+    build_hasher: BH,
+    storer: S,
+}
+impl<BH, S> Seeded<BH, S> {
+    /// New a storer use given build hasher
+    pub fn new(build_hasher: BH, storer: S) -> Self {
+        Self { build_hasher, storer }
+    }
+
+    /// Get the build hasher
+    pub fn build_hasher(&self) -> &BH {
+        &self.build_hasher
+    }
+}
+impl<BH, S: HashStorer> HashStorer for Seeded<BH, S> {
+    type HashCode = S::HashCode;
+
+    const CACHES: bool = S::CACHES;
+
+    fn clear(&mut self) {
+        self.storer.clear()
+    }
+
+    fn get(&self) -> Option<Self::HashCode> {
+        self.storer.get()
+    }
+
+    fn set(&self, code: Self::HashCode) {
+        self.storer.set(code)
+    }
+
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode,
+    {
+        self.storer.get_or_init(f)
+    }
+
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        S::write_code(code, state)
+    }
+}
+
+/// Inner hasher of [`How`], built by the [`BuildHasher`] stored in the [`Seeded`] storer
+///
+/// Each value may store a different instance, so cached hash codes of two values are not comparable,
+/// [`PartialEq`] of [`How`] compares the values only
+pub enum StoredBuildHasher { }
+
+impl<BH: BuildHasher, S: HashStorer> BuildInnerHasher<Seeded<BH, S>> for StoredBuildHasher {
+    type Hasher = BH::Hasher;
+
+    const COMPARABLE: bool = false;
+
+    fn build_inner_hasher(storer: &Seeded<BH, S>) -> Self::Hasher {
+        storer.build_hasher.build_hasher()
+    }
+}
+
+/// A [`How`] storing a [`BuildHasher`] instance, for stateful hashers e.g seeded
+///
+/// [`How`] takes the inner hasher by type and uses [`Default`],
+/// `SeededHow` calls [`BuildHasher::build_hasher`] of the stored instance instead
+///
+/// Hash codes depend on the instance, so keys in the same map should share it,
+/// and [`Borrowed`] lookup is not supported, the probe has no instance to hash with
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, SeededHow};
+/// # use std::{cell::Cell, collections::{HashSet, hash_map::RandomState}};
+/// let seed = RandomState::new();
+/// let key = |s: &str| SeededHow::with_hasher(s.to_owned(), seed.clone(), Cell::new(0u64));
+///
+/// let set = HashSet::from([key("a"), key("b")]);
+/// assert!(set.contains(&key("a")));
+/// assert!(! set.contains(&key("c")));
+/// assert!(How::is_hashed(set.get(&key("b")).unwrap()));
+/// ```
+///
+/// [`Borrowed`]: crate::Borrowed
+pub type SeededHow<T, BH = RandomState, S = Cell<u64>> = How<T, StoredBuildHasher, Seeded<BH, S>>;
+
+impl<T, BH, S: HashStorer> How<T, StoredBuildHasher, Seeded<BH, S>> {
+    /// New a wrapped value use given build hasher and storer
+    pub fn with_hasher(value: T, build_hasher: BH, storer: S) -> Self {
+        Self::new_in(value, Seeded::new(build_hasher, storer))
+    }
+}
+impl<T: ?Sized, BH, S> How<T, StoredBuildHasher, Seeded<BH, S>> {
+    /// Get the build hasher
+    pub fn build_hasher(this: &Self) -> &BH {
+        How::storer(this).build_hasher()
+    }
+}
@@ -0,0 +1,71 @@
+//! Serde mode preserving the cached hash code, use by `#[serde(with = "hash_on_write::serde_cached")]`
+//!
+//! Serialized as `(code: Option<u64>, value: T)`, deserialize restores the cache
+//!
+//! **Only sound with a stable inner hasher**, e.g not randomly seeded,
+//! and the same hasher and `Hash` implementation between serialize and deserialize,
+//! otherwise restored hash codes are wrong and lookups fail.
+//! In debug builds, the first few deserialized values in the process are re-hashed and asserted
+//!
+//! # Examples
+//! ```
+//! # use hash_on_write::How;
+//! # use serde::{Serialize, Deserialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Entry {
+//!     #[serde(with = "hash_on_write::serde_cached")]
+//!     key: How<String>,
+//! }
+//! let entry = Entry { key: How::new("foo".to_owned()) };
+//! How::make_hash(&entry.key);
+//!
+//! let json = serde_json::to_string(&entry).unwrap();
+//! let entry: Entry = serde_json::from_str(&json).unwrap();
+//! assert!(How::is_hashed(&entry.key));
+//! ```
+
+use core::{
+    hash::{Hash, Hasher},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{code_matches, FromHash, HashStorer, How};
+
+/// Count of values re-hashed for checking in debug builds
+const CHECK_FIRST: usize = 64;
+static CHECKED: AtomicUsize = AtomicUsize::new(0);
+
+/// Serialize `(code, value)`
+pub fn serialize<T, H, S, Se>(value: &How<T, H, S>, serializer: Se) -> Result<Se::Ok, Se::Error>
+where T: ?Sized + Serialize,
+      S: HashStorer,
+      Se: Serializer,
+{
+    (How::hash_code_u64(value), &value.value).serialize(serializer)
+}
+
+/// Deserialize `(code, value)` and restore the cache
+pub fn deserialize<'de, T, H, S, D>(deserializer: D) -> Result<How<T, H, S>, D::Error>
+where T: Deserialize<'de> + Hash,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      D: Deserializer<'de>,
+{
+    let (code, value) = <(Option<u64>, T)>::deserialize(deserializer)?;
+    let Some(code) = code else {
+        return Ok(How::new(value));
+    };
+
+    let code = S::HashCode::from_hash(code);
+    if cfg!(debug_assertions)
+        && CHECKED.fetch_add(1, Ordering::Relaxed) < CHECK_FIRST
+    {
+        let mut hasher = H::default();
+        value.hash(&mut hasher);
+        let new_code = S::HashCode::from_hash(hasher.finish());
+        debug_assert!(code_matches(code, new_code),
+            "restored hash code mismatch, the inner hasher is not stable");
+    }
+    Ok(How::new_with_code(value, code))
+}
@@ -0,0 +1,148 @@
+use core::{cell::Cell, fmt, marker::PhantomData};
+use serde::{
+    de::{self, DeserializeSeed, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{borrow::Cow, collections::hash_map::DefaultHasher};
+
+use crate::{Borrowed, How};
+
+/// Serialize as the wrapped value
+impl<T, H, S> Serialize for How<T, H, S>
+where T: ?Sized + Serialize,
+{
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        self.value.serialize(serializer)
+    }
+}
+/// Deserialize from the wrapped value, the hash cache is empty
+impl<'de, T, H, S> Deserialize<'de> for How<T, H, S>
+where T: Deserialize<'de>,
+      S: Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(How::new)
+    }
+}
+
+/// Serialize as the wrapped value, same as [`How`]
+impl<T, H, S> Serialize for Borrowed<T, H, S>
+where T: ?Sized + Serialize,
+{
+    fn serialize<Se: Serializer>(&self, serializer: Se) -> Result<Se::Ok, Se::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+/// [`DeserializeSeed`] for `How<T::Owned>`,
+/// deserialize from the wire format produced from a [`Borrowed<T>`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{Borrowed, BorrowedSeed, How};
+/// # use serde::de::DeserializeSeed;
+/// let json = serde_json::to_string(Borrowed::<str>::make_ref("foo")).unwrap();
+///
+/// let mut de = serde_json::Deserializer::from_str(&json);
+/// let x: How<String> = BorrowedSeed::<str>::new().deserialize(&mut de).unwrap();
+/// assert_eq!(*x, "foo");
+/// ```
+pub struct BorrowedSeed<T: ?Sized, H = DefaultHasher, S = Cell<u64>> {
+    _marker: PhantomData<fn() -> (H, S)>,
+    _value: PhantomData<fn(&T)>,
+}
+impl<T: ?Sized, H, S> BorrowedSeed<T, H, S> {
+    /// New a seed
+    pub fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+impl<T: ?Sized, H, S> Default for BorrowedSeed<T, H, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: ?Sized, H, S> Clone for BorrowedSeed<T, H, S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T: ?Sized, H, S> Copy for BorrowedSeed<T, H, S> { }
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    /// Neither [`Send`] nor [`Sync`]
+    struct NonSendHasher(PhantomData<*const ()>);
+
+    assert_send_sync::<BorrowedSeed<str, NonSendHasher, std::rc::Rc<Cell<u64>>>>();
+};
+impl<T: ?Sized, H, S> fmt::Debug for BorrowedSeed<T, H, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("BorrowedSeed")
+    }
+}
+impl<'de, T, H, S> DeserializeSeed<'de> for BorrowedSeed<T, H, S>
+where T: ?Sized + ToOwned,
+      T::Owned: Deserialize<'de>,
+      S: Default,
+{
+    type Value = How<T::Owned, H, S>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        How::deserialize(deserializer)
+    }
+}
+
+/// Zero-copy deserialize [`How<Cow<str>>`], use by `#[serde(deserialize_with = "hash_on_write::serde_borrow_cow_str")]`
+///
+/// Borrows from input when possible, e.g no escapes, otherwise owned
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::How;
+/// # use serde::Deserialize;
+/// # use std::borrow::Cow;
+/// #[derive(Deserialize)]
+/// struct Entry<'a> {
+///     #[serde(borrow, deserialize_with = "hash_on_write::serde_borrow_cow_str")]
+///     key: How<Cow<'a, str>>,
+/// }
+/// let entry: Entry = serde_json::from_str(r#"{"key": "foo"}"#).unwrap();
+/// assert!(matches!(*entry.key, Cow::Borrowed("foo")));
+///
+/// let entry: Entry = serde_json::from_str(r#"{"key": "f\"oo"}"#).unwrap();
+/// assert!(matches!(*entry.key, Cow::Owned(_)));
+/// ```
+///
+/// [`How<Cow<str>>`]: crate::How
+pub fn serde_borrow_cow_str<'de, H, S, D>(deserializer: D) -> Result<How<Cow<'de, str>, H, S>, D::Error>
+where S: Default,
+      D: Deserializer<'de>,
+{
+    struct CowStrVisitor;
+    impl<'de> Visitor<'de> for CowStrVisitor {
+        type Value = Cow<'de, str>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+            Ok(Cow::Borrowed(v))
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(v.to_owned()))
+        }
+
+        fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+            Ok(Cow::Owned(v))
+        }
+    }
+
+    deserializer.deserialize_str(CowStrVisitor).map(How::new)
+}
@@ -0,0 +1,161 @@
+use core::hash::{Hash, Hasher};
+use std::collections::HashMap;
+
+use crate::{HashStorer, How};
+
+fn index_by_code<T, H, S>(values: &[How<T, H, S>]) -> HashMap<S::HashCode, Vec<&How<T, H, S>>>
+where T: Hash,
+      H: Hasher + Default,
+      S: HashStorer,
+{
+    let mut index: HashMap<_, Vec<_>> = HashMap::with_capacity(values.len());
+    for value in values {
+        index.entry(How::make_hash(value))
+            .or_default()
+            .push(value);
+    }
+    index
+}
+
+fn filter_by_membership<'a, T, H, S>(
+    a: &'a [How<T, H, S>],
+    b: &[How<T, H, S>],
+    keep_contained: bool,
+) -> Vec<&'a How<T, H, S>>
+where T: Hash + Eq,
+      H: Hasher + Default,
+      S: HashStorer,
+{
+    let index = index_by_code(b);
+    a.iter()
+        .filter(|value| {
+            let contained = index.get(&How::make_hash(value))
+                .is_some_and(|candidates| {
+                    candidates.iter().any(|c| How::eq_value(c, value))
+                });
+            contained == keep_contained
+        })
+        .collect()
+}
+
+/// Elements of `a` which are not in `b`, keep the order and duplicates of `a`
+///
+/// `b` is indexed by hash codes, each element is hashed at most once,
+/// values are only compared on hash code matched
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, difference};
+/// let a = ["a", "b", "c"].map(How::new_default);
+/// let b = ["b"].map(How::new_default);
+/// assert_eq!(difference(&a, &b), [&a[0], &a[2]]);
+/// ```
+pub fn difference<'a, T, H, S>(a: &'a [How<T, H, S>], b: &[How<T, H, S>]) -> Vec<&'a How<T, H, S>>
+where T: Hash + Eq,
+      H: Hasher + Default,
+      S: HashStorer,
+{
+    filter_by_membership(a, b, false)
+}
+
+/// Elements of `a` which are also in `b`, keep the order and duplicates of `a`
+///
+/// Like [`difference`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, intersection};
+/// let a = ["a", "b", "c"].map(How::new_default);
+/// let b = ["b", "d"].map(How::new_default);
+/// assert_eq!(intersection(&a, &b), [&a[1]]);
+/// ```
+pub fn intersection<'a, T, H, S>(a: &'a [How<T, H, S>], b: &[How<T, H, S>]) -> Vec<&'a How<T, H, S>>
+where T: Hash + Eq,
+      H: Hasher + Default,
+      S: HashStorer,
+{
+    filter_by_membership(a, b, true)
+}
+
+/// Mode of [`join`]
+///
+/// [`join`]: crate::join
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinMode {
+    /// Only matched pairs
+    Inner,
+    /// Matched pairs and unmatched left
+    Left,
+    /// Matched pairs, unmatched left and unmatched right
+    Outer,
+}
+
+/// Hash join keyed pairs by cached hash codes
+///
+/// `right` is indexed by hash codes, values are only compared on hash code matched,
+/// each key is hashed at most once
+///
+/// Output order: left order, each left with matched rights in right order,
+/// then unmatched rights in [`JoinMode::Outer`]
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{How, join, JoinMode};
+/// let left = vec![(How::new_default("a"), 1), (How::new_default("b"), 2)];
+/// let right = vec![(How::new_default("b"), 'x'), (How::new_default("c"), 'y')];
+///
+/// let inner = join(left.clone(), right.clone(), JoinMode::Inner);
+/// assert_eq!(inner, [(How::new_default("b"), Some(2), Some('x'))]);
+///
+/// let outer = join(left, right, JoinMode::Outer);
+/// assert_eq!(outer.len(), 3);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn join<K, A, B, H, S>(
+    left: Vec<(How<K, H, S>, A)>,
+    right: Vec<(How<K, H, S>, B)>,
+    mode: JoinMode,
+) -> Vec<(How<K, H, S>, Option<A>, Option<B>)>
+where K: Hash + Eq + Clone,
+      A: Clone,
+      B: Clone,
+      H: Hasher + Default,
+      S: HashStorer + Clone,
+{
+    let mut index: HashMap<_, Vec<usize>> = HashMap::with_capacity(right.len());
+    for (i, (key, _)) in right.iter().enumerate() {
+        index.entry(How::make_hash(key))
+            .or_default()
+            .push(i);
+    }
+    let mut matched = vec![false; right.len()];
+    let mut result = Vec::with_capacity(left.len());
+
+    for (key, a) in left {
+        let matches: Vec<usize> = index.get(&How::make_hash(&key))
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|&i| How::eq_value(&right[i].0, &key))
+            .collect();
+
+        if matches.is_empty() {
+            if mode != JoinMode::Inner {
+                result.push((key, Some(a), None));
+            }
+            continue;
+        }
+        for i in matches {
+            matched[i] = true;
+            result.push((key.clone(), Some(a.clone()), Some(right[i].1.clone())));
+        }
+    }
+
+    if mode == JoinMode::Outer {
+        result.extend(right.into_iter()
+            .zip(matched)
+            .filter(|(_, matched)| ! matched)
+            .map(|((key, b), _)| (key, None, Some(b))));
+    }
+    result
+}
@@ -0,0 +1,116 @@
+//! Prehashed snapshot, save and load [`How`] with warm hash caches, enabled by `bincode` feature
+//!
+//! Snapshot records `(code, value)` encoded by [`bincode`],
+//! load restores the cached hash codes without running the inner hasher on values.
+//!
+//! **Only sound with a stable inner hasher**, e.g not randomly seeded,
+//! and not [`DefaultHasher`], whose algorithm may change between Rust releases.
+//! The header records the hash code width and a probe,
+//! the inner hasher result of a fixed input,
+//! loading refuses a snapshot of a different width or probe
+//!
+//! # Examples
+//! ```
+//! # use hash_on_write::{snapshot, Borrowed, How};
+//! # use std::{cell::Cell, collections::HashSet, hash::Hasher};
+//! /// FNV-1a, a stable algorithm
+//! struct Fnv(u64);
+//! impl Default for Fnv {
+//!     fn default() -> Self { Fnv(0xcbf2_9ce4_8422_2325) }
+//! }
+//! impl Hasher for Fnv {
+//!     fn finish(&self) -> u64 { self.0 }
+//!     fn write(&mut self, bytes: &[u8]) {
+//!         for &b in bytes {
+//!             self.0 = (self.0 ^ u64::from(b)).wrapping_mul(0x100_0000_01b3);
+//!         }
+//!     }
+//! }
+//! type FHow<T> = How<T, Fnv, Cell<u64>>;
+//!
+//! let keys: Vec<FHow<String>> = vec![How::new("foo".into()), How::new("bar".into())];
+//! keys.iter().for_each(|key| { How::make_hash(key); });
+//!
+//! let mut buf = Vec::new();
+//! snapshot::save(&keys, &mut buf).unwrap();
+//!
+//! let keys: Vec<FHow<String>> = snapshot::load(&mut &buf[..]).unwrap();
+//! assert!(keys.iter().all(How::is_hashed));
+//! let set = HashSet::<_>::from_iter(keys);
+//! assert!(set.contains(Borrowed::<_, Fnv, Cell<u64>>::make_ref("foo")));
+//! ```
+//!
+//! [`How`]: crate::How
+//! [`DefaultHasher`]: std::collections::hash_map::DefaultHasher
+
+use core::hash::Hasher;
+use std::io::{Read, Write};
+use bincode::{
+    config::standard,
+    decode_from_std_read,
+    encode_into_std_write,
+    error::{DecodeError, EncodeError},
+    Decode, Encode,
+};
+
+use crate::{FromHash, HashStorer, How};
+
+const MAGIC: [u8; 8] = *b"HOWSNAP\0";
+const VERSION: u32 = 2;
+const PROBE_INPUT: &[u8] = b"hash_on_write snapshot probe";
+
+/// Hash code width and inner hasher result of [`PROBE_INPUT`]
+fn hasher_id<H: Hasher + Default, S: HashStorer>() -> (u32, u64) {
+    let mut hasher = H::default();
+    hasher.write(PROBE_INPUT);
+    (S::HashCode::BITS, hasher.finish())
+}
+
+/// Write the header and `(code, value)` records of `keys`, uncached keys are saved uncached
+pub fn save<'a, T, H, S, W>(
+    keys: impl IntoIterator<Item = &'a How<T, H, S>>,
+    w: &mut W,
+) -> Result<(), EncodeError>
+where T: Encode + 'a,
+      H: Hasher + Default + 'a,
+      S: HashStorer + 'a,
+      W: Write,
+{
+    encode_into_std_write((MAGIC, VERSION, hasher_id::<H, S>()), w, standard())?;
+    for key in keys {
+        let record = (How::hash_code_u64(key), &key.value);
+        encode_into_std_write(Some(record), w, standard())?;
+    }
+    encode_into_std_write(None::<(Option<u64>, &T)>, w, standard())?;
+    Ok(())
+}
+
+/// Read a snapshot written by [`save`], the cached hash codes are restored
+///
+/// The inner hasher only runs once, for the header probe
+///
+/// Fails if the header is invalid or the hash code width or probe is different
+pub fn load<T, H, S, R>(r: &mut R) -> Result<Vec<How<T, H, S>>, DecodeError>
+where T: Decode<()>,
+      H: Hasher + Default,
+      S: HashStorer + Default,
+      R: Read,
+{
+    let (magic, version, id): ([u8; 8], u32, (u32, u64))
+        = decode_from_std_read(r, standard())?;
+    if magic != MAGIC || version != VERSION {
+        return Err(DecodeError::Other("invalid snapshot header"));
+    }
+    if id != hasher_id::<H, S>() {
+        return Err(DecodeError::Other("snapshot of a different hasher"));
+    }
+
+    let mut keys = Vec::new();
+    while let Some((code, value)) = decode_from_std_read::<Option<(Option<u64>, T)>, _, _>(r, standard())? {
+        keys.push(match code {
+            Some(code) => How::new_with_code(value, FromHash::from_hash(code)),
+            None => How::new(value),
+        });
+    }
+    Ok(keys)
+}
@@ -0,0 +1,66 @@
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    Database, Decode, Encode, Type,
+};
+
+use crate::{Borrowed, How};
+
+/// Same type as the wrapped value
+impl<T, H, S, DB> Type<DB> for How<T, H, S>
+where T: ?Sized + Type<DB>,
+      DB: Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        T::compatible(ty)
+    }
+}
+/// Encode as the wrapped value
+impl<'q, T, H, S, DB> Encode<'q, DB> for How<T, H, S>
+where T: Encode<'q, DB>,
+      DB: Database,
+{
+    fn encode(self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.value.encode(buf)
+    }
+
+    fn encode_by_ref(&self, buf: &mut DB::ArgumentBuffer<'q>) -> Result<IsNull, BoxDynError> {
+        self.value.encode_by_ref(buf)
+    }
+
+    fn produces(&self) -> Option<DB::TypeInfo> {
+        self.value.produces()
+    }
+
+    fn size_hint(&self) -> usize {
+        self.value.size_hint()
+    }
+}
+/// Decode from the wrapped value, the hash cache is empty
+impl<'r, T, H, S, DB> Decode<'r, DB> for How<T, H, S>
+where T: Decode<'r, DB>,
+      S: Default,
+      DB: Database,
+{
+    fn decode(value: DB::ValueRef<'r>) -> Result<Self, BoxDynError> {
+        T::decode(value).map(How::new)
+    }
+}
+
+/// Same type as the wrapped value
+impl<T, H, S, DB> Type<DB> for Borrowed<T, H, S>
+where T: ?Sized + Type<DB>,
+      DB: Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        T::compatible(ty)
+    }
+}
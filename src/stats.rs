@@ -0,0 +1,46 @@
+//! Process-wide hash statistics, enabled by `stats` feature
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+static COMPUTATIONS: AtomicU64 = AtomicU64::new(0);
+static HITS: AtomicU64 = AtomicU64::new(0);
+static INVALIDATIONS: AtomicU64 = AtomicU64::new(0);
+
+/// Snapshot of the counters
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Count of inner hasher runs
+    pub computations: u64,
+    /// Count of hash codes read from cache
+    pub hits: u64,
+    /// Count of cache clears by mutable access
+    pub invalidations: u64,
+}
+
+/// Get current counters
+pub fn stats() -> Stats {
+    Stats {
+        computations: COMPUTATIONS.load(Ordering::Relaxed),
+        hits: HITS.load(Ordering::Relaxed),
+        invalidations: INVALIDATIONS.load(Ordering::Relaxed),
+    }
+}
+
+/// Reset all counters to zero
+pub fn reset_stats() {
+    COMPUTATIONS.store(0, Ordering::Relaxed);
+    HITS.store(0, Ordering::Relaxed);
+    INVALIDATIONS.store(0, Ordering::Relaxed);
+}
+
+pub(crate) fn record_computation() {
+    COMPUTATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_hit() {
+    HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_invalidation() {
+    INVALIDATIONS.fetch_add(1, Ordering::Relaxed);
+}
@@ -1,13 +1,19 @@
 use std::{
     collections::{
         hash_map::{DefaultHasher, RandomState},
+        BTreeMap,
         HashMap,
     },
+    collections::HashSet,
     hash::BuildHasher,
+    ops::Bound,
     cell::Cell,
+    fmt::{Debug, Write},
+    rc::Rc,
+    sync::{atomic::{AtomicU32, AtomicU64, AtomicU8}, Arc},
 };
 
-use crate::{Borrowed, NoneStorer};
+use crate::{Borrowed, CacheNeutralMut, FrozenHow, HashStorer, HowBloom, MaybeHashed, NoneStorer};
 
 use super::How;
 
@@ -78,8 +84,9 @@ fn test_hash_map() {
     assert_eq!(map.get(&Borrowed::new("b")), Some(&-2));
     assert_eq!(map.get(&Borrowed::new("c")), Some(&-3));
 
+    #[allow(clippy::mutable_key_type)]
     let x: HashMap<How<String>, ()> = HashMap::new();
-    assert!(x.get(Borrowed::make_ref("a")).is_none());
+    assert!(! x.contains_key(Borrowed::make_ref("a")));
 }
 
 #[test]
@@ -119,3 +126,2427 @@ fn test_none_store() {
         }
     }
 }
+
+#[test]
+fn test_frozen() {
+    type AHow<T> = How<T, DefaultHasher, AtomicU64>;
+    let datas = ["foo", "", "test", "bar"];
+    let bh = RandomState::new();
+
+    for data in datas {
+        let frozen: FrozenHow<&str> = FrozenHow::new(data);
+        let how = How::new_default(data);
+        assert_eq!(bh.hash_one(frozen), bh.hash_one(&how));
+        assert_eq!(bh.hash_one(frozen), bh.hash_one(Borrowed::<str>::make_ref(data)));
+        assert_eq!(FrozenHow::hash_code(&frozen), How::make_hash(&how));
+
+        let how: AHow<&str> = frozen.into();
+        assert!(How::is_hashed(&how));
+        assert_eq!(How::hash_code(&how), Some(FrozenHow::hash_code(&frozen)));
+        assert_eq!(FrozenHow::<&str>::from(how), frozen);
+    }
+
+    let frozen_set: HashSet<FrozenHow<String>> = datas.iter()
+        .map(|&s| FrozenHow::new(s.to_owned()))
+        .collect();
+    #[allow(clippy::mutable_key_type)]
+    let how_set: HashSet<How<String>> = frozen_set.iter()
+        .cloned()
+        .map(How::from)
+        .collect();
+
+    for data in datas {
+        assert!(frozen_set.contains(Borrowed::make_ref(data)));
+        assert!(how_set.contains(Borrowed::make_ref(data)));
+        assert!(how_set.contains(&How::new(data.to_owned())));
+        assert!(frozen_set.contains(&FrozenHow::from(How::new_default(data.to_owned()))));
+    }
+    assert!(! frozen_set.contains(Borrowed::make_ref("baz")));
+    assert!(! how_set.contains(Borrowed::make_ref("baz")));
+}
+
+#[test]
+fn test_group_by_code() {
+    let datas = ["foo", "bar", "foo", "", "bar", "foo"];
+    let groups = How::group_by_code(datas.map(How::new_default));
+
+    assert_eq!(groups.len(), 3);
+    assert_eq!(groups.values().map(Vec::len).sum::<usize>(), datas.len());
+    for (code, group) in &groups {
+        assert!(group.iter().all(|how| How::hash_code(how) == Some(*code)));
+        assert!(group.iter().all(|how| *how == group[0]));
+    }
+    assert_eq!(groups[&How::make_hash(&How::new_default("foo"))].len(), 3);
+}
+
+#[test]
+fn test_new_in() {
+    let storer = Cell::new(0u64);
+    let a = How::<_, DefaultHasher, _>::new_in("foo", storer.clone());
+    How::make_hash(&a);
+
+    let b = How::<_, DefaultHasher, _>::new_in("bar", a.hashcode.clone());
+    assert!(! How::is_hashed(&b));
+    assert_ne!(How::make_hash(&a), How::make_hash(&b));
+
+    let v: How<Vec<u8>, DefaultHasher, _> = How::with_capacity_and_storer(16, AtomicU64::new(3));
+    assert!(! How::is_hashed(&v));
+    assert!(v.capacity() >= 16);
+    assert!(v.is_empty());
+
+    let s: How<Vec<u8>> = How::with_capacity(16);
+    assert!(s.capacity() >= 16);
+    assert_eq!(s, How::new_default(vec![]));
+}
+
+#[test]
+fn test_with_value() {
+    type NHow<T> = How<T, DefaultHasher, NoneStorer>;
+
+    let a: How<&str> = How::with_value("foo");
+    let b = NHow::with_value("foo");
+    let bh = RandomState::new();
+
+    assert_eq!(a, How::new_default("foo"));
+    assert_eq!(b, NHow::new("foo"));
+    assert_eq!(bh.hash_one(&a), bh.hash_one(&b));
+}
+
+#[cfg(not(feature = "strict-eq"))]
+#[test]
+fn test_dyn_key() {
+    use crate::DynKey;
+
+    type DHow = How<Box<dyn DynKey>>;
+    fn key<T: DynKey + 'static>(value: T) -> DHow {
+        How::new(Box::new(value))
+    }
+
+    #[allow(clippy::mutable_key_type)]
+    let mut set: HashSet<DHow> = HashSet::new();
+    assert!(set.insert(key(1u64)));
+    assert!(set.insert(key("1")));
+    assert!(set.insert(key(String::from("2"))));
+    assert!(! set.insert(key(1u64)));
+    assert!(! set.insert(key("1")));
+
+    assert!(set.contains(&key(1u64)));
+    assert!(set.contains(&key("1")));
+    assert!(set.contains(&key(String::from("2"))));
+    assert!(! set.contains(&key(2u64)));
+    assert!(! set.contains(&key("2")));
+    assert!(! set.contains(&key(1u32)));
+
+    assert!(key(1u64) != key(1u32));
+    assert!(key("2") != key(String::from("2")));
+    assert!(key("2") == key("2"));
+}
+
+#[test]
+fn test_fmt_write() {
+    let mut x = How::new_default(String::new());
+    let expected = How::new_default(String::from("a1b2"));
+    let (a, b) = ('a', "b");
+    How::make_hash(&x);
+
+    write!(x, "{a}{}{b}{}", 1, 2).unwrap();
+    assert!(! How::is_hashed(&x));
+    assert_eq!(*x, "a1b2");
+    assert_eq!(How::make_hash(&x), How::make_hash(&expected));
+
+    x.write_char('c').unwrap();
+    assert!(! How::is_hashed(&x));
+    assert_eq!(*x, "a1b2c");
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes_buf_mut() {
+    use bytes::{BufMut, BytesMut};
+
+    let mut x: How<BytesMut> = How::new(BytesMut::new());
+    let expected: How<BytesMut> = How::new(BytesMut::from(&b"ab\x00\x01cc"[..]));
+
+    How::make_hash(&x);
+    x.put_slice(b"ab");
+    assert!(! How::is_hashed(&x));
+
+    How::make_hash(&x);
+    x.put_u16(1);
+    assert!(! How::is_hashed(&x));
+
+    How::make_hash(&x);
+    x.put_bytes(b'c', 2);
+    assert!(! How::is_hashed(&x));
+
+    assert_eq!(x, expected);
+    assert_eq!(How::make_hash(&x), How::make_hash(&expected));
+}
+
+#[test]
+fn test_io_cursor() {
+    use std::io::{Cursor, Read, Write};
+
+    let mut x: How<_> = How::new(Cursor::new(vec![1u8, 2, 3]));
+
+    x.hashcode.set(1);
+    let mut buf = [0; 2];
+    x.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1, 2]);
+    assert!(How::is_hashed(&x));
+
+    x.write_all(&[4, 5]).unwrap();
+    assert!(! How::is_hashed(&x));
+    assert_eq!(x.get_ref(), &[1, 2, 4, 5]);
+}
+
+#[test]
+fn test_shared_narrow_atomic() {
+    type SHow<T> = How<T, DefaultHasher, Arc<AtomicU32>>;
+
+    let mut a = SHow::new("foo");
+    let b = a.clone();
+    assert!(! How::is_hashed(&b));
+
+    let code: u32 = How::make_hash(&a);
+    assert!(How::is_hashed(&b));
+    assert_eq!(How::hash_code(&b), Some(code));
+
+    let bh = RandomState::new();
+    assert_eq!(bh.hash_one(&a), bh.hash_one(&b));
+    assert_eq!(bh.hash_one(&a), bh.hash_one(Borrowed::<_, DefaultHasher, Arc<AtomicU32>>::new("foo")));
+
+    How::make_mut(&mut a);
+    assert!(! How::is_hashed(&a));
+    assert_eq!(How::hash_code(&b), Some(code));
+
+    let c: How<&str, DefaultHasher, AtomicU8> = How::new("foo");
+    assert_eq!(How::make_hash(&c), code as u8);
+}
+
+#[test]
+fn test_btree_map() {
+    #[allow(clippy::mutable_key_type)]
+    let mut map: BTreeMap<How<String>, i32> = BTreeMap::new();
+    let datas = ["foo", "bar", "", "baz"];
+
+    for (i, data) in datas.into_iter().enumerate() {
+        let key = How::new(data.to_owned());
+        if i % 2 == 0 { How::make_hash(&key); }
+        assert!(map.insert(key, i as i32).is_none());
+    }
+    assert!(map.keys().map(|k| k.as_str()).eq(["", "bar", "baz", "foo"]));
+
+    for (i, data) in datas.into_iter().enumerate() {
+        assert_eq!(map.get(Borrowed::make_ref(data)), Some(&(i as i32)));
+        assert_eq!(map.get(&How::new(data.to_owned())), Some(&(i as i32)));
+    }
+    assert_eq!(map.get(Borrowed::make_ref("qux")), None);
+
+    let bounds = (
+        Bound::Included(Borrowed::make_ref("b")),
+        Bound::Excluded(Borrowed::make_ref("c")),
+    );
+    let range: Vec<_> = map.range::<Borrowed<str>, _>(bounds)
+        .map(|(_, &v)| v)
+        .collect();
+    assert_eq!(range, [1, 3]);
+}
+
+#[test]
+fn test_storer() {
+    use std::sync::atomic::Ordering;
+
+    let a: How<_, DefaultHasher, Arc<AtomicU64>> = How::new("foo");
+    let b = a.clone();
+    assert_eq!(How::storer(&b).load(Ordering::Relaxed), 0);
+
+    let code = How::make_hash(&a);
+    assert_eq!(How::storer(&b).load(Ordering::Relaxed), code);
+    assert_eq!(Arc::strong_count(How::storer(&a)), 2);
+}
+
+#[test]
+fn test_maybe_hashed() {
+    let a = How::new_default("foo");
+    let b: How<_, DefaultHasher, AtomicU8> = How::new("foo");
+    let c = FrozenHow::<&str>::new("foo");
+    let d = Borrowed::<str>::make_ref("foo");
+
+    assert_eq!(a.cached_code(), None);
+    assert_eq!(b.cached_code(), None);
+    assert_eq!(c.cached_code(), Some(FrozenHow::hash_code(&c)));
+    assert_eq!(d.cached_code(), None);
+
+    let code = How::make_hash(&a);
+    let narrow_code = How::make_hash(&b);
+    assert_eq!(a.cached_code(), Some(code));
+    assert_eq!(b.cached_code(), Some(narrow_code.into()));
+    assert_eq!(c.cached_code(), Some(code));
+    assert_eq!(d.cached_code(), None);
+}
+
+#[test]
+fn test_bloom() {
+    use rand::random;
+
+    let random_key = || format!("{:x}", random::<u64>());
+    let keys: Vec<How<String>> = std::iter::repeat_with(random_key)
+        .take(1000)
+        .map(How::new)
+        .collect();
+    let bloom: HowBloom = HowBloom::from_keys(keys.iter(), 10000);
+    assert!(keys.iter().all(How::is_hashed));
+    assert_eq!(bloom.hashes(), 7);
+
+    for key in &keys {
+        assert!(bloom.maybe_contains(Borrowed::make_ref(key.as_str())));
+    }
+
+    let key_set: HashSet<&str> = keys.iter().map(|s| s.as_str()).collect();
+    let probes: Vec<String> = std::iter::repeat_with(random_key)
+        .filter(|s| ! key_set.contains(s.as_str()))
+        .take(10000)
+        .collect();
+    let false_positives = probes.iter()
+        .filter(|s| bloom.maybe_contains(Borrowed::make_ref(s.as_str())))
+        .count();
+    // expected rate about 0.8%
+    assert!(false_positives < 300, "{false_positives}");
+
+    let bytes = bloom.to_bytes();
+    let bloom: HowBloom = HowBloom::from_bytes(bytes.clone()).unwrap();
+    assert_eq!(bloom.to_bytes(), bytes);
+    for key in &keys {
+        assert!(bloom.maybe_contains(Borrowed::make_ref(key.as_str())));
+    }
+    assert!(HowBloom::<DefaultHasher>::from_bytes(vec![]).is_none());
+    assert!(HowBloom::<DefaultHasher>::from_bytes(vec![0, 1]).is_none());
+    assert!(HowBloom::<DefaultHasher>::from_bytes(vec![1]).is_none());
+}
+
+#[test]
+fn test_map_hash_code() {
+    let a = How::new_default("foo");
+    let b = How::new_default("bar");
+    let shared: How<_, DefaultHasher, Arc<AtomicU32>> = How::new("foo");
+    let shared_clone = shared.clone();
+
+    How::map_hash_code(&a, |_| 0);
+    assert!(! How::is_hashed(&a));
+
+    let (code_a, code_b) = (How::make_hash(&a), How::make_hash(&b));
+    How::map_hash_code(&a, |code| code ^ code_b);
+    assert_eq!(How::hash_code(&a), Some(code_a ^ code_b));
+
+    How::map_hash_code(&b, |_| 0);
+    assert!(How::is_hashed(&b));
+    assert_ne!(How::hash_code(&b), Some(0));
+
+    let code = How::make_hash(&shared);
+    How::map_hash_code(&shared, |code| !code);
+    assert_eq!(How::hash_code(&shared_clone), Some(!code));
+}
+
+#[test]
+fn test_from_ref() {
+    let value = String::from("foo");
+    let x: How<&String> = How::from_ref(&value);
+    let code = How::make_hash_checked(&x);
+    assert_eq!(code, How::make_hash(&How::new_default(value.clone())));
+    assert_eq!(How::make_hash_checked(&x), code);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic = "stale hash cache"]
+fn test_from_ref_stale() {
+    struct Key(Cell<u64>);
+    impl std::hash::Hash for Key {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.0.get().hash(state)
+        }
+    }
+
+    let value = Key(Cell::new(1));
+    let x: How<&Key> = How::from_ref(&value);
+    How::make_hash_checked(&x);
+    value.0.set(2);
+    How::make_hash_checked(&x);
+}
+
+#[test]
+fn test_group_by_shard() {
+    use crate::{group_by_shard, MAX_SHARD_BITS};
+
+    let datas = ["foo", "bar", "baz", "foo", "", "bar", "foo", "qux"];
+    let groups = How::group_by_code(datas.map(How::new_default));
+    assert_eq!(groups.len(), 5);
+    assert!(groups.values().flatten().all(How::is_hashed));
+
+    for bits in [0, 1, 3, 8] {
+        let shards = group_by_shard(datas.map(How::new_default), bits);
+        assert_eq!(shards.len(), 1 << bits);
+        assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), datas.len());
+        assert!(shards.iter().flatten().all(How::is_hashed));
+
+        for data in datas {
+            let containing = shards.iter()
+                .filter(|shard| shard.iter().any(|x| **x == data))
+                .count();
+            assert_eq!(containing, 1);
+        }
+    }
+
+    for bits in [8, 64, u32::MAX] {
+        let shards = group_by_shard(datas.map(How::<_, DefaultHasher, AtomicU8>::new), bits);
+        assert_eq!(shards.len(), 256);
+    }
+
+    for bits in [MAX_SHARD_BITS, 64, u32::MAX] {
+        let shards = group_by_shard(datas.map(How::new_default), bits);
+        assert_eq!(shards.len(), 1 << MAX_SHARD_BITS);
+        assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), datas.len());
+    }
+}
+
+#[test]
+fn test_composite_hash() {
+    type THow<T> = How<T, DefaultHasher, Rc<Cell<u64>>>;
+
+    let a = THow::new("foo");
+    let b = THow::new(String::from("bar"));
+    How::make_hash(&a);
+    let x = How::composite(a.clone(), b.clone());
+    assert!(! How::is_hashed(&x));
+
+    How::make_hash(&b);
+    let y = How::composite(a.clone(), b.clone());
+    assert!(How::is_hashed(&y));
+    assert_eq!(How::make_hash(&x), How::make_hash(&y));
+    assert_eq!(x, y);
+
+    #[allow(clippy::mutable_key_type)]
+    let mut map = HashMap::new();
+    map.insert(y, 1);
+    assert_eq!(map.get(&x), Some(&1));
+    assert_eq!(map.get(&How::composite(THow::new("foo"), THow::new("bar".into()))), Some(&1));
+    assert_eq!(map.get(&How::composite(THow::new("bar"), THow::new("foo".into()))), None);
+
+    let (a1, b1) = How::into_inner(x).into_parts();
+    assert_eq!((a1, b1), (a, b));
+}
+
+#[test]
+fn test_caches() {
+    fn check<S: HashStorer + Default>() {
+        let x: How<_, DefaultHasher, S> = How::new("foo");
+        How::make_hash(&x);
+        assert_eq!(How::is_hashed(&x), S::CACHES);
+    }
+
+    const _: () = assert!(! <NoneStorer>::CACHES);
+    const _: () = assert!(<Cell<u64>>::CACHES);
+    const _: () = assert!(! <Arc<NoneStorer>>::CACHES);
+    const _: () = assert!(! How::<&str, DefaultHasher, Rc<NoneStorer>>::CACHES);
+    const _: () = assert!(How::<&str, DefaultHasher, AtomicU8>::CACHES);
+
+    check::<Cell<u64>>();
+    check::<Cell<u8>>();
+    check::<AtomicU64>();
+    check::<AtomicU32>();
+    check::<NoneStorer>();
+    check::<NoneStorer<u8>>();
+    check::<Rc<Cell<u64>>>();
+    check::<Arc<AtomicU64>>();
+    check::<Arc<NoneStorer>>();
+}
+
+thread_local! {
+    static FINISH_COUNT: Cell<usize> = const { Cell::new(0) };
+    static WRITE_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// [`DefaultHasher`] but count [`Hasher::finish`] and [`Hasher::write`] calls of current thread
+///
+/// Counts are per hash computation,
+/// `debug-hasher` in debug builds hashes twice on each computation
+///
+/// [`Hasher::finish`]: std::hash::Hasher::finish
+/// [`Hasher::write`]: std::hash::Hasher::write
+#[derive(Default)]
+struct CountingHasher(DefaultHasher);
+impl CountingHasher {
+    const HASHES_PER_COMPUTATION: usize = if cfg!(all(feature = "debug-hasher", debug_assertions)) { 2 } else { 1 };
+
+    fn count() -> usize {
+        FINISH_COUNT.with(Cell::get) / Self::HASHES_PER_COMPUTATION
+    }
+
+    fn writes() -> usize {
+        WRITE_COUNT.with(Cell::get) / Self::HASHES_PER_COMPUTATION
+    }
+}
+impl std::hash::Hasher for CountingHasher {
+    fn finish(&self) -> u64 {
+        FINISH_COUNT.with(|n| n.set(n.get() + 1));
+        self.0.finish()
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        WRITE_COUNT.with(|n| n.set(n.get() + 1));
+        self.0.write(bytes)
+    }
+}
+
+#[test]
+fn test_set_ops() {
+    use crate::{difference, intersection};
+    use rand::random;
+    type CHow = How<u8, CountingHasher>;
+
+    for _ in 0..50 {
+        let a_values: Vec<u8> = (0..random::<usize>() % 100).map(|_| random::<u8>() % 64).collect();
+        let b_values: Vec<u8> = (0..random::<usize>() % 100).map(|_| random::<u8>() % 64).collect();
+        let a: Vec<CHow> = a_values.iter().copied().map(How::new).collect();
+        let b: Vec<CHow> = b_values.iter().copied().map(How::new).collect();
+
+        let count = CountingHasher::count();
+        let diff: Vec<u8> = difference(&a, &b).into_iter().map(|x| **x).collect();
+        let inter: Vec<u8> = intersection(&a, &b).into_iter().map(|x| **x).collect();
+        assert_eq!(CountingHasher::count() - count, a.len() + b.len());
+
+        let b_set: HashSet<u8> = b_values.iter().copied().collect();
+        let expected_diff: Vec<u8> = a_values.iter().copied().filter(|x| ! b_set.contains(x)).collect();
+        let expected_inter: Vec<u8> = a_values.iter().copied().filter(|x| b_set.contains(x)).collect();
+        assert_eq!(diff, expected_diff);
+        assert_eq!(inter, expected_inter);
+    }
+}
+
+#[test]
+fn test_narrow_none_storer() {
+    fn check<T, S, N>(data: &str)
+    where T: Debug + Eq + crate::FromHash + std::hash::Hash,
+          S: HashStorer<HashCode = T> + Default,
+          N: HashStorer<HashCode = T> + Default,
+    {
+        let a: How<&str, DefaultHasher, S> = How::new(data);
+        let b: How<&str, DefaultHasher, N> = How::new(data);
+        let bh = RandomState::new();
+
+        assert_eq!(How::make_hash(&a), How::make_hash(&b));
+        assert_eq!(bh.hash_one(&a), bh.hash_one(&b));
+        assert_eq!(bh.hash_one(&a), bh.hash_one(Borrowed::<_, DefaultHasher, N>::new(data)));
+        assert!(! How::is_hashed(&b));
+    }
+
+    for data in ["foo", "", "test", "bar"] {
+        check::<u8, Cell<u8>, NoneStorer<u8>>(data);
+        check::<u16, Cell<u16>, NoneStorer<u16>>(data);
+        check::<u32, Cell<u32>, NoneStorer<u32>>(data);
+        check::<u32, AtomicU32, NoneStorer<u32>>(data);
+        check::<u64, Cell<u64>, NoneStorer>(data);
+    }
+}
+
+#[test]
+fn test_join() {
+    use crate::{join, JoinMode};
+    use rand::random;
+    type CHow = How<u8, CountingHasher>;
+
+    fn nested_loop(
+        left: &[(u8, u32)],
+        right: &[(u8, u32)],
+        mode: JoinMode,
+    ) -> Vec<(u8, Option<u32>, Option<u32>)> {
+        let mut result = vec![];
+        for &(k, a) in left {
+            let matches: Vec<_> = right.iter().filter(|(k1, _)| *k1 == k).collect();
+            if matches.is_empty() && mode != JoinMode::Inner {
+                result.push((k, Some(a), None));
+            }
+            result.extend(matches.into_iter().map(|&(_, b)| (k, Some(a), Some(b))));
+        }
+        if mode == JoinMode::Outer {
+            result.extend(right.iter()
+                .filter(|(k, _)| left.iter().all(|(k1, _)| k1 != k))
+                .map(|&(k, b)| (k, None, Some(b))));
+        }
+        result
+    }
+
+    for _ in 0..50 {
+        let random_pairs = || (0..random::<usize>() % 50)
+            .map(|_| (random::<u8>() % 32, random::<u32>()))
+            .collect::<Vec<_>>();
+        let (left, right) = (random_pairs(), random_pairs());
+        let wrap = |pairs: &[(u8, u32)]| pairs.iter()
+            .map(|&(k, v)| (CHow::new(k), v))
+            .collect::<Vec<_>>();
+
+        for mode in [JoinMode::Inner, JoinMode::Left, JoinMode::Outer] {
+            let count = CountingHasher::count();
+            let result: Vec<_> = join(wrap(&left), wrap(&right), mode)
+                .into_iter()
+                .map(|(k, a, b)| (How::into_inner(k), a, b))
+                .collect();
+            assert_eq!(CountingHasher::count() - count, left.len() + right.len());
+            assert_eq!(result, nested_loop(&left, &right, mode));
+        }
+    }
+}
+
+#[test]
+fn test_hash_code_u64() {
+    let a: How<_, DefaultHasher, AtomicU8> = How::new("foo");
+    let b: How<_, DefaultHasher, Rc<Cell<u32>>> = How::new("foo");
+    let c: How<_, DefaultHasher, NoneStorer<u16>> = How::new("foo");
+    assert_eq!(How::hash_code_u64(&a), None);
+
+    let code_a = How::make_hash(&a);
+    let code_b = How::make_hash(&b);
+    How::make_hash(&c);
+    assert_eq!(How::hash_code_u64(&a), Some(code_a as u64));
+    assert_eq!(How::hash_code_u64(&b), Some(code_b as u64));
+    assert_eq!(How::hash_code_u64(&c), None);
+    assert_eq!(How::storer(&b).get_u64(), Some(code_b as u64));
+}
+
+#[test]
+fn test_convert_storer() {
+    let a: How<_> = How::new(String::from("foo"));
+    let b: How<_, DefaultHasher, Arc<AtomicU64>> = How::convert_storer(a.clone());
+    assert!(! How::is_hashed(&b));
+
+    let code = How::make_hash(&a);
+    let b: How<_, DefaultHasher, Arc<AtomicU64>> = How::convert_storer(a.clone());
+    assert_eq!(How::hash_code(&b), Some(code));
+
+    let c: How<_, DefaultHasher, NoneStorer> = How::convert_storer(b);
+    assert!(! How::is_hashed(&c));
+    assert_eq!(How::make_hash(&c), code);
+
+    let d: How<_, DefaultHasher, Cell<u32>> = How::convert_storer(a.clone());
+    assert!(! How::is_hashed(&d));
+    assert_eq!(How::make_hash(&d), code as u32);
+    let e: How<_, DefaultHasher, AtomicU32> = How::convert_storer(d);
+    assert_eq!(How::hash_code(&e), Some(code as u32));
+    assert_eq!(e, How::<_, DefaultHasher, AtomicU32>::new(String::from("foo")));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    fn to_cbor<T: serde::Serialize + ?Sized>(value: &T) -> Vec<u8> {
+        let mut buf = vec![];
+        ciborium::into_writer(value, &mut buf).unwrap();
+        buf
+    }
+
+    let x = How::new_default(String::from("foo"));
+    How::make_hash(&x);
+    assert_eq!(serde_json::to_string(&x).unwrap(), serde_json::to_string("foo").unwrap());
+    assert_eq!(to_cbor(&x), to_cbor("foo"));
+
+    let y: How<String> = serde_json::from_str("\"foo\"").unwrap();
+    assert!(! How::is_hashed(&y));
+    assert_eq!(x, y);
+
+    let map: HashMap<String, u32> = [("a", 1), ("b", 2), ("", 3)]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v))
+        .collect();
+    #[allow(clippy::mutable_key_type)]
+    let how_map: HashMap<How<String>, u32> = map.iter()
+        .map(|(k, &v)| (How::new(k.clone()), v))
+        .collect();
+
+    let json = serde_json::to_string(&how_map).unwrap();
+    assert_eq!(serde_json::from_str::<HashMap<String, u32>>(&json).unwrap(), map);
+    #[allow(clippy::mutable_key_type)]
+    let decoded: HashMap<How<String>, u32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, how_map);
+    assert_eq!(decoded.get(Borrowed::make_ref("b")), Some(&2));
+
+    let cbor = to_cbor(&how_map);
+    #[allow(clippy::mutable_key_type)]
+    let decoded: HashMap<How<String>, u32> = ciborium::from_reader(&cbor[..]).unwrap();
+    assert_eq!(decoded, how_map);
+    assert_eq!(to_cbor(&How::<_>::new(vec![1u8, 2])), to_cbor(&vec![1u8, 2]));
+}
+
+#[test]
+fn test_as_str_slice() {
+    let s = How::new_default(String::from("foo"));
+    let v = How::new_default(vec![1, 2, 3]);
+    let code = How::make_hash(&s);
+    How::make_hash(&v);
+
+    assert_eq!(How::as_str(&s), "foo");
+    assert_eq!(How::as_slice(&v), [1, 2, 3]);
+    assert_eq!(How::hash_code(&s), Some(code));
+    assert!(How::is_hashed(&v));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_cached() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Entry {
+        #[serde(with = "crate::serde_cached")]
+        key: How<String>,
+        #[serde(with = "crate::serde_cached")]
+        narrow: How<String, DefaultHasher, AtomicU32>,
+        value: u32,
+    }
+
+    let entries: Vec<Entry> = ["foo", "bar", "", "baz"].into_iter()
+        .enumerate()
+        .map(|(i, s)| Entry {
+            key: How::new(s.to_owned()),
+            narrow: How::new(s.to_owned()),
+            value: i as u32,
+        })
+        .collect();
+    entries.iter().step_by(2).for_each(|e| { How::make_hash(&e.key); How::make_hash(&e.narrow); });
+
+    let json = serde_json::to_string(&entries).unwrap();
+    let decoded: Vec<Entry> = serde_json::from_str(&json).unwrap();
+
+    for (i, (a, b)) in entries.iter().zip(&decoded).enumerate() {
+        assert_eq!(How::is_hashed(&b.key), i % 2 == 0);
+        assert_eq!(How::hash_code(&a.key), How::hash_code(&b.key));
+        assert_eq!(How::hash_code(&a.narrow), How::hash_code(&b.narrow));
+        assert_eq!(a.value, b.value);
+    }
+
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<How<String>> = decoded.into_iter().map(|e| e.key).collect();
+    for s in ["foo", "bar", "", "baz"] {
+        assert!(set.contains(&How::new(s.to_owned())));
+        assert!(set.contains(Borrowed::make_ref(s)));
+    }
+}
+
+#[cfg(all(feature = "serde", debug_assertions))]
+#[test]
+#[should_panic = "restored hash code mismatch"]
+fn test_serde_cached_mismatch() {
+    let mut de = serde_json::Deserializer::from_str("[1, \"foo\"]");
+    let _: How<String> = crate::serde_cached::deserialize(&mut de).unwrap();
+}
+
+#[test]
+fn test_boxed_slice() {
+    let values: Vec<String> = ["foo", "bar", ""].map(String::from).into();
+    let x: Box<How<[String]>> = How::boxed_slice(values.clone().into_boxed_slice());
+    assert_eq!(x.as_ref(), &values[..]);
+    assert!(! How::is_hashed(&x));
+    assert_eq!(How::make_hash(&x), How::make_hash(&How::new_default(values)));
+
+    let mut y: Box<How<[u8], DefaultHasher, AtomicU8>> = How::boxed_slice(Box::new([]));
+    assert!(y.is_empty());
+    How::make_hash(&y);
+    assert!(How::is_hashed(&y));
+    How::make_mut(&mut y);
+    assert!(! How::is_hashed(&y));
+
+    let z: Box<How<[()], DefaultHasher, NoneStorer>> = How::boxed_slice(vec![(); 3].into());
+    assert_eq!(z.len(), 3);
+
+    let w: Box<How<[u64]>> = How::boxed_slice(vec![1, 2].into());
+    let w1: Box<How<[u64]>> = Box::new(How::new([1, 2]));
+    assert_eq!(w, w1);
+}
+
+#[test]
+fn test_fmt_write_loop() {
+    let mut x: How<String, DefaultHasher, AtomicU64> = How::new(String::new());
+    let mut expected = String::new();
+
+    for i in 0..10 {
+        How::make_hash(&x);
+        assert!(How::is_hashed(&x));
+        write!(x, "{i},").unwrap();
+        assert!(! How::is_hashed(&x));
+
+        How::make_hash(&x);
+        x.write_str("-").unwrap();
+        assert!(! How::is_hashed(&x));
+
+        How::make_hash(&x);
+        x.write_char('|').unwrap();
+        assert!(! How::is_hashed(&x));
+
+        expected += &format!("{i},-|");
+    }
+    assert_eq!(*x, expected);
+    assert_eq!(How::make_hash(&x), How::make_hash(&How::<_, DefaultHasher, AtomicU64>::new(expected)));
+}
+
+#[cfg(not(feature = "strict-eq"))]
+#[test]
+fn test_cmp_by_code_then_value() {
+    use rand::random;
+    use std::cmp::Ordering;
+    type CHow = How<u8, DefaultHasher, Cell<u8>>;
+
+    let values: Vec<u8> = (0..500).map(|_| random()).collect();
+    let mut hows: Vec<CHow> = values.iter().copied().map(How::new).collect();
+    hows.iter().step_by(3).for_each(|x| { How::make_hash(x); });
+
+    hows.sort_by(How::cmp_by_code_then_value);
+    assert!(hows.iter().all(How::is_hashed));
+    assert!(hows.windows(2).all(|w| How::cmp_by_code_then_value(&w[0], &w[1]) != Ordering::Greater));
+    for w in hows.windows(2) {
+        if How::hash_code(&w[0]) == How::hash_code(&w[1]) {
+            assert!(*w[0] <= *w[1]);
+        }
+    }
+
+    hows.dedup();
+    let unique: HashSet<u8> = values.into_iter().collect();
+    assert_eq!(hows.len(), unique.len());
+
+    let (a, b) = (CHow::new(1), CHow::new(1));
+    assert_eq!(How::cmp_by_code_then_value(&a, &b), Ordering::Equal);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_borrow() {
+    use serde::Deserialize;
+    use std::borrow::Cow;
+
+    #[derive(Deserialize)]
+    struct Entry<'a> {
+        #[serde(borrow)]
+        key: How<&'a str>,
+        #[serde(borrow, deserialize_with = "crate::serde_borrow_cow_str")]
+        cow: How<Cow<'a, str>>,
+    }
+
+    let input = String::from(r#"{"key": "foo", "cow": "bar"}"#);
+    let entry: Entry = serde_json::from_str(&input).unwrap();
+    assert_eq!(*entry.key, "foo");
+    assert!(input.as_bytes().as_ptr_range().contains(&entry.key.as_ptr()));
+    assert!(matches!(*entry.cow, Cow::Borrowed("bar")));
+    assert!(! How::is_hashed(&entry.cow));
+    assert_eq!(How::make_hash(&entry.cow), How::make_hash(&How::new_default("bar")));
+
+    let input = r#"{"key": "foo", "cow": "b\"ar"}"#;
+    let entry: Entry = serde_json::from_str(input).unwrap();
+    assert!(matches!(*entry.cow, Cow::Owned(ref s) if s == "b\"ar"));
+
+    let input = r#"{"key": "f\"oo", "cow": "bar"}"#;
+    assert!(serde_json::from_str::<Entry>(input).is_err());
+
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<How<&str>, u32> = serde_json::from_str(r#"{"a": 1, "b": 2}"#).unwrap();
+    assert_eq!(map.get(Borrowed::make_ref("b")), Some(&2));
+}
+
+#[test]
+fn test_from_display() {
+    let a: How<String> = How::from_display(format_args!("{}-{}", 1, 2));
+    let b: How<String> = How::new("1-2".into());
+    assert_eq!(a, b);
+    assert_eq!(How::make_hash(&a), How::make_hash(&b));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_borrowed_seed() {
+    use crate::BorrowedSeed;
+    use serde::de::DeserializeSeed;
+
+    let keys: Vec<&Borrowed<str>> = vec![Borrowed::make_ref("a"), Borrowed::make_ref("b")];
+    let json = serde_json::to_string(&keys).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+
+    #[allow(clippy::mutable_key_type)]
+    let store: HashSet<How<String>> = serde_json::from_str(&json).unwrap();
+    for key in &keys {
+        assert!(store.contains(*key));
+    }
+
+    let json = serde_json::to_string(Borrowed::<[u8]>::make_ref(&[1, 2])).unwrap();
+    let mut de = serde_json::Deserializer::from_str(&json);
+    let x = BorrowedSeed::<[u8]>::new().deserialize(&mut de).unwrap();
+    assert!(! How::is_hashed(&x));
+    assert_eq!(*x, [1, 2]);
+    assert!(HashSet::from([x]).contains(Borrowed::<[u8]>::make_ref(&[1, 2])));
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn test_pattern() {
+    let pat: How<&str> = How::new("oo");
+    assert!("foo".contains(pat.clone()));
+    assert_eq!("foobar".find(pat.clone()), Some(1));
+    assert_eq!("foo".strip_suffix(pat), Some("f"));
+    assert_eq!("a,b".split(How::<char>::new(',')).collect::<Vec<_>>(), ["a", "b"]);
+
+    let pat: How<String> = How::new("ba".into());
+    How::make_hash(&pat);
+    assert!("foobar".contains(&pat));
+    assert_eq!("barbar".strip_prefix(&pat), Some("rbar"));
+    assert!(How::is_hashed(&pat));
+}
+
+#[test]
+fn test_shared_storer_clear() {
+    fn check<S>(is_shared: impl Fn(&S, &S) -> bool)
+    where S: HashStorer + Default + Clone,
+          S::HashCode: Debug,
+    {
+        let a: How<String, DefaultHasher, S> = How::new("foo".into());
+        let mut b = a.clone();
+        let c = a.clone();
+        assert!(is_shared(&a.hashcode, &b.hashcode));
+
+        let code = How::make_hash(&b);
+        assert_eq!(How::hash_code(&a), Some(code));
+
+        b.push('!');
+        assert!(! is_shared(&a.hashcode, &b.hashcode));
+        assert!(is_shared(&a.hashcode, &c.hashcode));
+        assert!(! How::is_hashed(&b));
+        assert_eq!(How::hash_code(&a), Some(code));
+        assert_eq!(How::hash_code(&c), Some(code));
+
+        let mut a = a;
+        drop(c);
+        a.push('!');
+        assert!(! How::is_hashed(&a));
+        assert_eq!(How::make_hash(&a), How::make_hash(&b));
+    }
+
+    check::<Rc<Cell<u64>>>(Rc::ptr_eq);
+    check::<Rc<Cell<u8>>>(Rc::ptr_eq);
+    check::<Arc<AtomicU64>>(Arc::ptr_eq);
+    check::<Arc<Cell<u64>>>(Arc::ptr_eq);
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_rkyv() {
+    use rkyv::{rancor::Error, Archived};
+
+    type Keys = Vec<How<String>>;
+    let keys: Keys = ["a", "b", "c"].map(|s| How::new(s.to_owned())).into();
+    How::make_hash(&keys[0]);
+    How::make_hash(&keys[2]);
+
+    let bytes = rkyv::to_bytes::<Error>(&keys).unwrap();
+    let archived = rkyv::access::<Archived<Keys>, Error>(&bytes).unwrap();
+    assert_eq!(archived.len(), 3);
+    assert_eq!(archived[1].as_str(), "b");
+    for (how, archived) in keys.iter().zip(archived.iter()) {
+        assert_eq!(How::hash_code(how), archived.hash_code());
+    }
+
+    let restored: Keys = rkyv::deserialize::<Keys, Error>(archived).unwrap();
+    assert_eq!(restored, keys);
+    assert!(How::is_hashed(&restored[0]));
+    assert!(! How::is_hashed(&restored[1]));
+    assert_eq!(How::hash_code(&restored[2]), How::hash_code(&keys[2]));
+
+    let mut bytes = bytes.to_vec();
+    bytes.truncate(bytes.len() - 1);
+    assert!(rkyv::access::<Archived<Keys>, Error>(&bytes).is_err());
+
+    type NarrowHow = How<u32, DefaultHasher, Cell<u16>>;
+    let x: NarrowHow = How::new(3);
+    let code = How::make_hash(&x);
+    let bytes = rkyv::to_bytes::<Error>(&x).unwrap();
+    let restored: NarrowHow = rkyv::from_bytes::<_, Error>(&bytes).unwrap();
+    assert_eq!(How::hash_code(&restored), Some(code));
+}
+
+#[test]
+fn test_inspect() {
+    let mut x: How<Vec<u8>> = How::new(vec![1, 2]);
+    let code = How::make_hash(&x);
+    assert_eq!(How::inspect(&x, |v| v.iter().sum::<u8>()), 3);
+    assert_eq!(How::hash_code(&x), Some(code));
+
+    let len = How::inspect_mut(&mut x, |v| {
+        v.pop();
+        v.len()
+    });
+    assert_eq!(len, 1);
+    assert!(! How::is_hashed(&x));
+    assert_eq!(How::make_hash(&x), How::make_hash(&How::new_default(vec![1u8])));
+}
+
+#[cfg(feature = "nightly")]
+#[test]
+fn test_unsize() {
+    use crate::DynKey;
+
+    let x: How<Box<u32>> = How::new(Box::new(3));
+    How::make_hash(&x);
+    let y: How<Box<dyn DynKey>> = How::unsize(x);
+    assert!(! How::is_hashed(&y));
+    assert_eq!(How::make_hash(&y), How::make_hash(&How::new_default(Box::new(3u32) as Box<dyn DynKey>)));
+
+    let x: How<Rc<[u8; 2]>, DefaultHasher, Arc<AtomicU64>> = How::new(Rc::new([1, 2]));
+    let shared = x.clone();
+    How::make_hash(&x);
+    let y: How<Rc<[u8]>, _, _> = How::unsize(x);
+    assert!(! How::is_hashed(&y));
+    assert!(How::is_hashed(&shared));
+}
+
+#[test]
+fn test_narrow_code_distribution() {
+    use crate::FromHash;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    assert_eq!(u8::from_hash(0x1200), 0x12);
+    assert_eq!(u16::from_hash(0x1234_0000_0000), 0x1234);
+    assert_eq!(u64::from_hash(0x1200), 0x1200);
+    assert_eq!(u8::from_hash(0), 0);
+
+    const PER_CODE: usize = 4096;
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    let mut counts = [0usize; 256];
+    for _ in 0..PER_CODE * 255 {
+        let hash: u64 = rng.gen();
+        let storer = Cell::<u8>::default();
+        counts[storer.get_or_init(|| FromHash::from_hash(hash)) as usize] += 1;
+    }
+    assert_eq!(counts[0], 0);
+    for (code, &count) in counts.iter().enumerate().skip(1) {
+        assert!(count.abs_diff(PER_CODE) < PER_CODE / 10, "code {code:#x} count {count}");
+    }
+
+    let mut counts = [0usize; 256];
+    for _ in 0..PER_CODE {
+        let hash: u64 = rng.gen::<u64>() << 8;
+        counts[u8::from_hash(hash) as usize] += 1;
+    }
+    assert!(counts[<u8 as FromHash>::ZERO_MAPPED as usize] < PER_CODE / 64);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_bincode() {
+    use bincode::config;
+
+    fn check<C: bincode::config::Config>(config: C) {
+        let key: &Borrowed<str> = Borrowed::make_ref("foo");
+        let x: How<String> = How::new("foo".into());
+        How::make_hash(&x);
+        let bytes = bincode::encode_to_vec(&x, config).unwrap();
+        assert_eq!(bytes, bincode::encode_to_vec(key, config).unwrap());
+        assert_eq!(bytes, bincode::encode_to_vec("foo", config).unwrap());
+
+        let (y, len): (How<String>, _) = bincode::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(len, bytes.len());
+        assert!(! How::is_hashed(&y));
+        assert_eq!(y, x);
+
+        let (y, _): (How<&str>, _) = bincode::borrow_decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(*y, "foo");
+
+        #[allow(clippy::mutable_key_type)]
+        let map: HashMap<How<String>, u64> = (0..16)
+            .map(|i| (How::new(i.to_string()), i))
+            .collect();
+        let bytes = bincode::encode_to_vec(&map, config).unwrap();
+        #[allow(clippy::mutable_key_type)]
+        let (decoded, _): (HashMap<How<String>, u64>, _) = bincode::decode_from_slice(&bytes, config).unwrap();
+        assert_eq!(decoded, map);
+        assert_eq!(decoded.get(Borrowed::make_ref("7")), Some(&7));
+    }
+
+    check(config::standard());
+    check(config::standard().with_fixed_int_encoding());
+    check(config::legacy());
+}
+
+#[cfg(feature = "smol_str")]
+#[test]
+fn test_smol_str() {
+    use crate::aliases::SmolHow;
+    use smol_str::SmolStr;
+
+    let long = "a string longer than the inline capacity of SmolStr";
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<SmolHow, usize> = ["foo", long]
+        .into_iter()
+        .map(|s| (How::new(SmolStr::new(s)), s.len()))
+        .collect();
+    assert_eq!(map.get(Borrowed::make_ref("foo")), Some(&3));
+    assert_eq!(map.get(Borrowed::make_ref(long)), Some(&long.len()));
+    assert_eq!(map.get(Borrowed::make_ref("bar")), None);
+
+    let key = map.keys().find(|k| k.len() > 3).unwrap();
+    assert!(How::is_hashed(key));
+    let cloned = key.clone();
+    assert!(How::is_hashed(&cloned));
+    assert_eq!(How::make_hash(&cloned), How::make_hash(&How::new_default(long.to_owned())));
+}
+
+#[test]
+fn test_make_hash_computes_once() {
+    fn check<S: HashStorer + Default>() {
+        let mut x: How<String, CountingHasher, S> = How::new("foo".into());
+        let outer = RandomState::new();
+
+        let count = CountingHasher::count();
+        let code = How::make_hash(&x);
+        let outer_code = outer.hash_one(&x);
+        for _ in 0..10 {
+            assert!(How::make_hash(&x) == code);
+            assert_eq!(outer.hash_one(&x), outer_code);
+        }
+        assert_eq!(CountingHasher::count() - count, 1);
+
+        How::make_mut(&mut x).push('!');
+        for _ in 0..10 {
+            How::make_hash(&x);
+            outer.hash_one(&x);
+        }
+        assert_eq!(CountingHasher::count() - count, 2);
+    }
+
+    check::<Cell<u64>>();
+    check::<Cell<u8>>();
+    check::<AtomicU64>();
+    check::<AtomicU8>();
+    check::<Rc<Cell<u64>>>();
+    check::<Arc<AtomicU32>>();
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn test_schemars() {
+    use schemars::{schema_for, JsonSchema};
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Wrapped {
+        name: How<String>,
+        data: How<Vec<u8>, DefaultHasher, AtomicU64>,
+        tags: Vec<How<String>>,
+        alias: Option<How<String>>,
+    }
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Plain {
+        name: String,
+        data: Vec<u8>,
+        tags: Vec<String>,
+        alias: Option<String>,
+    }
+
+    let mut wrapped = schema_for!(Wrapped);
+    let mut plain = schema_for!(Plain);
+    wrapped.remove("title");
+    plain.remove("title");
+    assert_eq!(wrapped, plain);
+    assert_eq!(How::<str>::schema_name(), str::schema_name());
+}
+
+#[cfg(feature = "compact_str")]
+#[test]
+fn test_compact_str() {
+    use crate::aliases::CompactHow;
+
+    let mut x: CompactHow<DefaultHasher, AtomicU64> = How::new("foo".into());
+    let code = How::make_hash(&x);
+    assert_eq!(code, How::make_hash(&How::<_, DefaultHasher, AtomicU64>::new("foo".to_owned())));
+
+    How::make_mut(&mut x).push_str(&"o".repeat(64));
+    assert!(x.is_heap_allocated());
+    assert!(! How::is_hashed(&x));
+
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<_, _> = [(x, 1)].into();
+    let key = format!("foo{}", "o".repeat(64));
+    assert_eq!(map.get(Borrowed::make_ref(key.as_str())), Some(&1));
+}
+
+#[test]
+fn test_borrow_cow() {
+    use std::borrow::Cow;
+
+    let x: How<Vec<u8>, DefaultHasher, AtomicU64> = How::new(vec![1, 2]);
+    How::make_hash(&x);
+    let key: Cow<Borrowed<[u8], _, _>> = How::borrow_cow(&x);
+    assert!(matches!(key, Cow::Borrowed(_)));
+    assert_eq!(key.value, [1, 2]);
+
+    let owned = key.into_owned();
+    assert!(! How::is_hashed(&owned));
+    assert_eq!(owned, x);
+    assert_eq!(How::make_hash(&owned), How::make_hash(&x));
+
+    let s: &Borrowed<str> = Borrowed::make_ref("foo");
+    let owned: How<String> = s.to_owned();
+    assert_eq!(*owned, "foo");
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck() {
+    use bytemuck::TransparentWrapper;
+
+    let mut keys = vec!["a".to_owned(), "b".to_owned()];
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<How<String>> = keys.iter().cloned().map(How::new).collect();
+
+    let borrowed: &[Borrowed<String>] = TransparentWrapper::wrap_slice(&keys);
+    assert!(borrowed.iter().all(|key| set.contains(key)));
+    let inner: &[String] = TransparentWrapper::peel_slice(borrowed);
+    assert_eq!(inner.as_ptr(), keys.as_ptr());
+
+    let borrowed: &mut [Borrowed<String>] = TransparentWrapper::wrap_slice_mut(&mut keys);
+    borrowed[0].push('!');
+    assert_eq!(keys[0], "a!");
+    assert!(! set.contains(Borrowed::<String>::make_ref(&keys[0])));
+
+    let x: &Borrowed<str> = TransparentWrapper::wrap_ref("b");
+    assert!(set.contains(x));
+}
+
+#[test]
+fn test_graceful_hasher() {
+    use crate::{FromHash, GracefulHasher};
+    use std::hash::Hasher;
+
+    #[derive(Default)]
+    struct PanicOnWrite(DefaultHasher);
+    impl Hasher for PanicOnWrite {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            assert!(bytes != b"bad", "bad bytes");
+            self.0.write(bytes)
+        }
+    }
+
+    type GHow<T> = How<T, GracefulHasher<PanicOnWrite>>;
+    let good: GHow<&[u8]> = How::new(b"good");
+    let bad: GHow<&[u8]> = How::new(b"bad");
+    assert_eq!(How::make_hash(&good), How::make_hash(&How::new_default(&b"good"[..])));
+    assert_eq!(How::make_hash(&bad), <u64 as FromHash>::ZERO_MAPPED);
+
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<GHow<&[u8]>> = [good, bad].into();
+    assert!(set.contains(Borrowed::<[u8], _>::make_ref(b"bad")));
+
+    let narrow: How<&[u8], GracefulHasher<PanicOnWrite, u16>, Cell<u16>> = How::new(b"bad");
+    assert_eq!(How::make_hash(&narrow), <u16 as FromHash>::ZERO_MAPPED);
+
+    let mut hasher = GracefulHasher::<PanicOnWrite>::default();
+    hasher.write(b"bad");
+    assert!(hasher.panicked());
+    hasher.write(b"good");
+    assert_eq!(hasher.finish(), <u64 as FromHash>::ZERO_MAPPED);
+}
+
+#[test]
+fn test_passthrough_hasher() {
+    use crate::PassthroughBuildHasher;
+
+    let build = PassthroughBuildHasher::default();
+    let x: How<String> = How::new("foo".into());
+    let code = How::make_hash(&x);
+    assert_eq!(build.hash_one(&x), code);
+    assert_eq!(build.hash_one(Borrowed::<str>::make_ref("foo")), code);
+
+    let narrow: How<&str, DefaultHasher, AtomicU32> = How::new("foo");
+    assert_eq!(build.hash_one(&narrow), u64::from(How::make_hash(&narrow)));
+
+    let count = CountingHasher::count();
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<How<u32, CountingHasher>, u32, PassthroughBuildHasher> = (0..64)
+        .map(|i| (How::new(i), i))
+        .collect();
+    let keys: Vec<How<u32, CountingHasher>> = map.keys().cloned().collect();
+    assert_eq!(CountingHasher::count() - count, 64);
+    for _ in 0..4 {
+        for key in &keys {
+            assert_eq!(map[key], **key);
+        }
+    }
+    assert_eq!(CountingHasher::count() - count, 64);
+
+    assert_ne!(build.hash_one((1u64, 2u64)), build.hash_one((2u64, 1u64)));
+    assert_ne!(build.hash_one("ab"), build.hash_one("ba"));
+    assert_ne!(build.hash_one((0u64, 1u64)), build.hash_one(1u64));
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn test_arbitrary() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+
+    type Map = HashMap<How<String>, u8>;
+
+    let mut rng = StdRng::seed_from_u64(0xf022);
+    let (mut hashed, mut unhashed) = (0, 0);
+    for _ in 0..256 {
+        let mut data = vec![0; 256];
+        rng.fill_bytes(&mut data);
+        let mut u = Unstructured::new(&data);
+
+        let Ok(x) = How::<String>::arbitrary(&mut u) else { continue };
+        if How::is_hashed(&x) {
+            hashed += 1;
+            assert_eq!(How::hash_code(&x), Some(How::make_hash(&How::new_default(String::clone(&x)))));
+        } else {
+            unhashed += 1;
+        }
+
+        #[allow(clippy::mutable_key_type)]
+        let Ok(map) = Map::arbitrary(&mut u) else { continue };
+        for (key, value) in &map {
+            assert!(How::is_hashed(key));
+            assert_eq!(map.get(Borrowed::make_ref(key.as_str())), Some(value));
+        }
+    }
+    assert!(hashed > 0 && unhashed > 0);
+
+    let x = How::<u32>::arbitrary_take_rest(Unstructured::new(&[1, 2, 0, 0, 0])).unwrap();
+    assert!(How::is_hashed(&x));
+    assert_eq!(*x, 2);
+    assert_eq!(How::<u32>::size_hint(0), (5, Some(5)));
+}
+
+#[test]
+fn test_seeded_how() {
+    use crate::SeededHow;
+
+    let (seed1, seed2) = (RandomState::new(), RandomState::new());
+    let key = |s: &str, seed: &RandomState| {
+        SeededHow::with_hasher(s.to_owned(), seed.clone(), Cell::new(0u64))
+    };
+
+    let a1 = key("a", &seed1);
+    let a2 = key("a", &seed2);
+    assert!(! How::is_hashed(&a1));
+    assert!(a1 == a2);
+    assert_eq!(How::make_hash(&a1), How::build_hasher(&a1).hash_one("a"));
+    assert_eq!(How::make_hash(&a1), seed1.hash_one("a"));
+    assert_ne!(How::make_hash(&a1), How::make_hash(&a2));
+    assert!(a1 == a2, "equality must not depend on cache state");
+    assert!(! How::hash_differs(&a1, &a2));
+    assert!(a1 != key("b", &seed2));
+
+    #[allow(clippy::mutable_key_type)]
+    let set1 = HashSet::from([key("a", &seed1), key("b", &seed1)]);
+    #[allow(clippy::mutable_key_type)]
+    let set2 = HashSet::from([key("a", &seed2)]);
+    assert!(set1.contains(&key("b", &seed1)));
+    assert!(set2.contains(&key("a", &seed2)));
+    assert!(! set2.contains(&key("b", &seed2)));
+
+    let mut b = key("b", &seed1);
+    How::make_hash(&b);
+    b.push('!');
+    assert!(! How::is_hashed(&b));
+    assert!(! set1.contains(&b));
+    assert_eq!(How::into_inner(b), "b!");
+}
+
+#[test]
+fn test_inspect_hash() {
+    let x: How<&str, CountingHasher, Cell<u16>> = How::new("foo");
+    let count = CountingHasher::count();
+    let mut seen = None;
+    How::inspect_hash(&x, |code| seen = Some(code));
+    assert_eq!(seen, None);
+    assert_eq!(CountingHasher::count(), count);
+
+    let code = How::make_hash(&x);
+    How::inspect_hash(&x, |code| seen = Some(code));
+    assert_eq!(seen, Some(code));
+    assert_eq!(CountingHasher::count() - count, 1);
+}
+
+#[cfg(feature = "proptest")]
+#[test]
+fn test_proptest_strategy() {
+    use ::proptest::{
+        collection::vec,
+        strategy::{Strategy, ValueTree},
+        test_runner::TestRunner,
+    };
+    use crate::proptest::how;
+
+    let mut runner = TestRunner::deterministic();
+    let (mut hashed, mut unhashed) = (0, 0);
+    for _ in 0..64 {
+        let mut tree = how(vec(0u8..10, 0..8)).new_tree(&mut runner).unwrap();
+        let is_hashed = tree.is_hashed();
+        if is_hashed { hashed += 1 } else { unhashed += 1 }
+        loop {
+            let x = tree.current();
+            assert_eq!(How::is_hashed(&x), is_hashed);
+            if is_hashed {
+                assert_eq!(How::hash_code(&x), Some(How::make_hash(&How::new_default(Vec::clone(&x)))));
+            }
+            if ! tree.simplify() { break }
+        }
+        assert!(tree.current().is_empty());
+    }
+    assert!(hashed > 0 && unhashed > 0);
+
+    runner.run(&(how(0u8..4), how(0u8..4)), |(a, b)| {
+        let state = RandomState::new();
+        assert_eq!(a == b, *a == *b);
+        if a == b {
+            assert_eq!(state.hash_one(&a), state.hash_one(&b));
+        }
+        Ok(())
+    }).unwrap();
+}
+
+#[test]
+fn test_or_compute() {
+    let mut x: How<Vec<u8>, DefaultHasher, AtomicU8> = How::new(vec![1, 2]);
+    let code = How::or_compute(&x, |v| v.len() as u8);
+    assert_eq!(code, 2);
+    assert_eq!(How::make_hash(&x), 2);
+
+    How::make_mut(&mut x).clear();
+    assert_ne!(How::or_compute(&x, |_| 0), 0);
+    assert_eq!(How::hash_code(&x), Some(<u8 as crate::FromHash>::ZERO_MAPPED));
+}
+
+#[test]
+fn test_probe_str() {
+    type AHow<T> = How<T, DefaultHasher, AtomicU32>;
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<AHow<String>, i32> = HashMap::from([(How::new("foo".to_owned()), 1)]);
+    let key: AHow<String> = How::new("foo".to_owned());
+    assert_eq!(map.get(How::probe_str(&key)), Some(&1));
+    assert!(! How::is_hashed(&key));
+    assert!(std::ptr::eq(&How::probe_str(&key).value, How::as_str(&key)));
+}
+
+#[test]
+#[allow(clippy::mutable_key_type)]
+fn test_as_ref_borrowed() {
+    fn contains<K>(set: &HashSet<How<String>>, key: &K) -> bool
+    where K: AsRef<Borrowed<String>>,
+    {
+        set.contains(key.as_ref())
+    }
+
+    let x: How<String> = How::new("foo".to_owned());
+    let set = HashSet::from([x.clone()]);
+    assert!(contains(&set, &x));
+    assert!(contains(&set, &Borrowed::new("foo".to_owned())));
+    assert!(! contains(&set, &How::new("bar".to_owned())));
+
+    let r: &String = &x;
+    assert_eq!(r, "foo");
+    assert_eq!(x.len(), 3);
+}
+
+#[cfg(feature = "rand")]
+#[test]
+fn test_rand() {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    let gen = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let a: How<u64> = rng.gen();
+        let b: How<[u8; 4], DefaultHasher, AtomicU32> = How::random_hashed(&mut rng);
+        (a, b)
+    };
+    let (a1, b1) = gen(7);
+    let (a2, b2) = gen(7);
+    assert!(! How::is_hashed(&a1));
+    assert!(How::is_hashed(&b1));
+    assert_eq!((*a1, *b1), (*a2, *b2));
+    assert_eq!(How::hash_code(&b1), How::hash_code(&b2));
+
+    let mut rng = StdRng::seed_from_u64(7);
+    assert_eq!(rng.gen::<u64>(), *a1);
+}
+
+#[test]
+fn test_cache_poison_detector() {
+    use crate::CachePoisonDetector;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    type PHow<T> = How<T, DefaultHasher, CachePoisonDetector<Cell<u64>>>;
+
+    let mut x: PHow<String> = How::new("foo".into());
+    let code = How::make_hash(&x);
+    assert_eq!(How::make_hash(&x), code);
+    assert_eq!(code, How::make_hash(&How::new_default("foo".to_owned())));
+    How::make_mut(&mut x).push('!');
+    assert!(! How::is_hashed(&x));
+    How::make_hash(&x);
+    How::make_hash(&x);
+
+    struct Interior(Cell<u8>);
+    impl std::hash::Hash for Interior {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.0.get().hash(state)
+        }
+    }
+    let y: PHow<Interior> = How::new(Interior(Cell::new(1)));
+    How::make_hash(&y);
+    y.0.set(2);
+    let result = catch_unwind(AssertUnwindSafe(|| How::make_hash(&y)));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_retain_cache() {
+    #[derive(PartialEq, Eq)]
+    struct Entry {
+        key: String,
+        len: usize,
+    }
+    impl std::hash::Hash for Entry {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.key.hash(state)
+        }
+    }
+
+    let mut x: How<Entry, CountingHasher> = How::new(Entry { key: "foo".into(), len: 0 });
+    let count = CountingHasher::count();
+    let code = How::make_hash(&x);
+    let len = How::retain_cache(&mut x, |entry| {
+        entry.len = entry.key.len();
+        entry.len
+    });
+    assert_eq!(len, 3);
+    assert_eq!(How::hash_code(&x), Some(code));
+    assert_eq!(How::make_hash(&x), code);
+    assert_eq!(CountingHasher::count() - count, 1);
+}
+
+#[test]
+#[cfg(feature = "strict-eq")]
+fn test_strict_eq() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    type SHow = How<u32, DefaultHasher, Cell<u8>>;
+
+    let a: SHow = How::new(0);
+    let b = (1..).map(SHow::new)
+        .find(|b| How::make_hash(b) == How::make_hash(&a))
+        .unwrap();
+    let c = SHow::new(0);
+    assert!(a == c);
+
+    How::make_hash(&c);
+    assert!(a == c);
+    let unhashed = SHow::new(*b);
+    assert!(a != unhashed);
+
+    let result = catch_unwind(AssertUnwindSafe(|| a == b));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_type_tagged() {
+    use crate::TypeTagged;
+
+    type THow<T> = How<T, DefaultHasher, TypeTagged<Cell<u64>, T>>;
+
+    let state = RandomState::new();
+    let a: THow<u32> = How::new(1);
+    let b: THow<i32> = How::new(1);
+    let plain: How<u32> = How::new(1);
+    assert_eq!(How::make_hash(&a), How::make_hash(&plain));
+    assert_ne!(state.hash_one(&a), state.hash_one(&plain));
+    assert_ne!(state.hash_one(&a), state.hash_one(&b));
+    assert_eq!(state.hash_one(&a), state.hash_one(THow::<u32>::new(1)));
+
+    let key: &Borrowed<u32, DefaultHasher, TypeTagged<Cell<u64>, u32>> = Borrowed::make_ref(&1);
+    assert_eq!(state.hash_one(key), state.hash_one(&a));
+
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<THow<Vec<u8>>> = HashSet::from([How::new(vec![1, 2])]);
+    assert!(set.contains(Borrowed::<[u8], _, _>::make_ref(&[1, 2])));
+}
+
+#[test]
+fn test_hash_differs() {
+    let a = How::new_default("foo".to_owned());
+    let b = How::new_default("foo".to_owned());
+    How::make_hash(&a);
+    assert!(! How::hash_differs(&a, &b));
+    How::make_hash(&b);
+    assert!(! How::hash_differs(&a, &b));
+
+    let mut c = b.clone();
+    How::make_mut(&mut c).push('!');
+    How::make_hash(&c);
+    assert!(How::hash_differs(&a, &c));
+    assert_ne!(a, c);
+
+    let x: How<_, DefaultHasher, NoneStorer> = How::new("foo");
+    let y: How<_, DefaultHasher, NoneStorer> = How::new("bar");
+    How::make_hash(&x);
+    How::make_hash(&y);
+    assert!(! How::hash_differs(&x, &y));
+}
+
+#[test]
+fn test_new_with_code() {
+    use crate::FromHash;
+
+    let x: How<&str> = How::new_with_code("foo", 0);
+    assert_eq!(How::hash_code(&x), Some(<u64 as FromHash>::ZERO_MAPPED));
+
+    let code = How::make_hash(&How::new_default("foo"));
+    let x: How<&str, DefaultHasher, AtomicU64> = How::new_with_code("foo", code);
+    assert!(How::is_hashed(&x));
+    assert_eq!(How::make_hash(&x), code);
+
+    let x: How<&str, DefaultHasher, NoneStorer> = How::new_with_code("foo", code);
+    assert!(! How::is_hashed(&x));
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn test_tracing_events() {
+    use std::sync::Mutex;
+    use tracing::{field::{Field, Visit}, Event, Subscriber};
+    use tracing_subscriber::{layer::{Context, SubscriberExt}, Layer, Registry};
+
+    #[derive(Default)]
+    struct Collect(Arc<Mutex<Vec<String>>>);
+    struct Message<'a>(&'a mut String);
+    impl Visit for Message<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            if field.name() == "message" {
+                *self.0 = format!("{value:?}");
+            }
+        }
+    }
+    impl<S: Subscriber> Layer<S> for Collect {
+        fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+            assert_eq!(event.metadata().target(), "hash_on_write::trace");
+            let mut message = String::new();
+            event.record(&mut Message(&mut message));
+            self.0.lock().unwrap().push(message);
+        }
+    }
+
+    let collect = Collect::default();
+    let events = collect.0.clone();
+    tracing::subscriber::with_default(Registry::default().with(collect), || {
+        let mut x = How::new_default("foo".to_owned());
+        How::make_hash(&x);
+        How::make_hash(&x);
+        How::make_mut(&mut x).push('!');
+        How::make_hash(&x);
+    });
+    assert_eq!(*events.lock().unwrap(), [
+        "hash computed",
+        "hash cache hit",
+        "hash cache invalidated",
+        "hash computed",
+    ]);
+}
+
+#[test]
+fn test_eq_value() {
+    type UHow<T> = How<T, DefaultHasher, NoneStorer>;
+
+    let a: How<String> = How::new("foo".to_owned());
+    let b: UHow<String> = How::new("foo".to_owned());
+    let c: How<String, DefaultHasher, Cell<u8>> = How::new("bar".to_owned());
+    How::make_hash(&a);
+    How::make_hash(&c);
+    assert!(How::eq_value(&a, &b));
+    assert!(How::eq_value(&b, &a));
+    assert!(! How::eq_value(&a, &c));
+    assert!(a == *b);
+}
+
+#[cfg(not(feature = "strict-eq"))]
+#[test]
+fn test_eq_matrix() {
+    /// Every hash code collides
+    #[derive(Default)]
+    struct Collide(Cell<u64>);
+    impl HashStorer for Collide {
+        type HashCode = u64;
+
+        fn clear(&mut self) {
+            HashStorer::clear(&mut self.0)
+        }
+
+        fn get(&self) -> Option<u64> {
+            HashStorer::get(&self.0)
+        }
+
+        fn set(&self, _code: u64) {
+            HashStorer::set(&self.0, 1)
+        }
+
+        fn get_or_init<F>(&self, _f: F) -> u64
+        where F: FnOnce() -> u64,
+        {
+            self.0.get_or_init(|| 1)
+        }
+    }
+
+    fn check<S: HashStorer + Default>(hash_a: bool, hash_b: bool) {
+        let make = |s: &str, hashed| {
+            let x: How<String, DefaultHasher, S> = How::new(s.to_owned());
+            if hashed { How::make_hash(&x); }
+            x
+        };
+        let a = make("foo", hash_a);
+        assert!(a == make("foo", hash_b), "{hash_a} {hash_b}");
+        assert!(a != make("bar", hash_b), "{hash_a} {hash_b}");
+    }
+
+    for hash_a in [false, true] {
+        for hash_b in [false, true] {
+            check::<Cell<u64>>(hash_a, hash_b);
+            check::<Collide>(hash_a, hash_b);
+        }
+    }
+
+    let a: How<_, DefaultHasher, Collide> = How::new("foo");
+    let b: How<_, DefaultHasher, Collide> = How::new("bar");
+    assert_eq!(How::make_hash(&a), How::make_hash(&b));
+    assert!(a != b);
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_snapshot() {
+    use crate::snapshot;
+    use std::hash::Hasher;
+
+    /// Same as [`DefaultHasher`] but a different algorithm
+    struct Salted(DefaultHasher);
+    impl Default for Salted {
+        fn default() -> Self {
+            let mut hasher = DefaultHasher::new();
+            hasher.write_u8(1);
+            Salted(hasher)
+        }
+    }
+    impl Hasher for Salted {
+        fn finish(&self) -> u64 {
+            self.0.finish()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes)
+        }
+    }
+    type CHow<T> = How<T, CountingHasher>;
+
+    let keys: Vec<CHow<String>> = (0..100)
+        .map(|i| How::new(i.to_string()))
+        .collect();
+    keys[..90].iter().for_each(|key| { How::make_hash(key); });
+
+    let mut buf = Vec::new();
+    snapshot::save(&keys, &mut buf).unwrap();
+
+    let count = CountingHasher::count();
+    let loaded: Vec<CHow<String>> = snapshot::load(&mut &buf[..]).unwrap();
+    assert_eq!(CountingHasher::count() - count, 1, "only the header probe");
+    assert_eq!(loaded, keys);
+    assert_eq!(loaded.iter().filter(|key| How::is_hashed(key)).count(), 90);
+    for (a, b) in loaded.iter().zip(&keys) {
+        assert_eq!(How::hash_code(a), How::hash_code(b));
+    }
+
+    let count = CountingHasher::count();
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<CHow<String>> = loaded.into_iter().collect();
+    assert_eq!(CountingHasher::count() - count, 10);
+    assert!(set.contains(Borrowed::make_ref("42")));
+    assert!(set.contains(Borrowed::make_ref("95")));
+    assert!(! set.contains(Borrowed::make_ref("100")));
+
+    // same algorithm, the codes are valid
+    let same = snapshot::load::<String, DefaultHasher, Cell<u64>, _>(&mut &buf[..]).unwrap();
+    assert!(same.iter().zip(&keys).all(|(a, b)| How::hash_code(a) == How::hash_code(b)));
+    let other = snapshot::load::<String, Salted, Cell<u64>, _>(&mut &buf[..]);
+    assert!(other.is_err());
+    let other = snapshot::load::<String, CountingHasher, Cell<u8>, _>(&mut &buf[..]);
+    assert!(other.is_err());
+    assert!(snapshot::load::<String, CountingHasher, Cell<u64>, _>(&mut &buf[1..]).is_err());
+}
+
+#[cfg(feature = "debug-hasher")]
+#[test]
+fn test_debug_hasher() {
+    use std::{hash::{Hash, Hasher}, panic::{catch_unwind, AssertUnwindSafe}};
+
+    for _ in 0..2 {
+        let a = How::new_default("foo".to_owned());
+        assert_eq!(How::make_hash(&a), How::make_hash(&How::new_default("foo".to_owned())));
+        #[allow(clippy::mutable_key_type)]
+        let set = HashSet::from([a, How::new_default("bar".to_owned())]);
+        assert!(set.contains(Borrowed::make_ref("foo")));
+    }
+
+    /// Writes a different count on each hash
+    struct Inconsistent(Cell<u8>);
+    impl Hash for Inconsistent {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.set(self.0.get() + 1);
+            self.0.get().hash(state)
+        }
+    }
+    let x = How::new_default(Inconsistent(Cell::new(0)));
+    let result = catch_unwind(AssertUnwindSafe(|| How::make_hash(&x)));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+
+    thread_local! {
+        static SEED: Cell<u64> = const { Cell::new(0) };
+    }
+    #[derive(Default)]
+    struct Drift(DefaultHasher);
+    impl Hasher for Drift {
+        fn finish(&self) -> u64 {
+            SEED.set(SEED.get() + 1);
+            self.0.finish() ^ SEED.get()
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes)
+        }
+    }
+    let y = How::<_, Drift>::new("foo");
+    let result = catch_unwind(AssertUnwindSafe(|| How::make_hash(&y)));
+    assert_eq!(result.is_err(), cfg!(debug_assertions));
+}
+
+#[test]
+fn test_changed_since() {
+    let mut values: HashMap<&'static str, How<Vec<i32>>> = HashMap::from([
+        ("a", How::new_default(vec![1])),
+        ("b", How::new_default(vec![2])),
+    ]);
+    let mut seen: HashMap<&'static str, u64> = HashMap::new();
+    let mut propagate = |values: &HashMap<&'static str, How<Vec<i32>>>| {
+        let mut dirty: Vec<&'static str> = values.iter()
+            .filter(|&(&k, v)| seen.get(k).is_none_or(|&code| How::changed_since(v, code)))
+            .map(|(&k, _)| k)
+            .collect();
+        for &k in &dirty {
+            seen.insert(k, How::make_hash(&values[k]));
+        }
+        dirty.sort_unstable();
+        dirty
+    };
+
+    assert_eq!(propagate(&values), ["a", "b"]);
+    assert!(propagate(&values).is_empty());
+
+    values.get_mut("b").unwrap().push(3);
+    assert_eq!(propagate(&values), ["b"]);
+
+    How::make_mut(values.get_mut("a").unwrap());
+    assert!(propagate(&values).is_empty());
+}
+
+#[test]
+fn test_from_vec_collections() {
+    let set: How<HashSet<&str>, DefaultHasher, AtomicU64> = vec!["a", "b", "a"].into();
+    assert_eq!(*set, HashSet::from(["a", "b"]));
+    assert!(! How::is_hashed(&set));
+
+    let map: How<HashMap<&str, i32>> = vec![("a", 1), ("b", 2), ("a", 3)].into();
+    assert_eq!(*map, HashMap::from([("a", 3), ("b", 2)]));
+    assert!(! How::is_hashed(&map));
+}
+
+#[test]
+fn test_none_storer_custom_code() {
+    use crate::FromHash;
+
+    /// Custom code type, not `Debug` or `Default`
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    struct Code(u32);
+    impl FromHash for Code {
+        const ZERO_MAPPED: Self = Code(u32::ZERO_MAPPED);
+        const BITS: u32 = u32::BITS;
+
+        fn from_hash(hash: u64) -> Self {
+            Code(u32::from_hash(hash))
+        }
+
+        fn to_u64(self) -> u64 {
+            self.0.into()
+        }
+    }
+
+    let storer = NoneStorer::<Code>::default();
+    assert_eq!(format!("{storer:?}"), "NoneStorer");
+    assert_eq!(storer, storer.clone());
+
+    let x: How<&str, DefaultHasher, NoneStorer<Code>> = How::new("foo");
+    let code = How::make_hash(&x);
+    assert!(! How::is_hashed(&x));
+    assert!(code == Code(How::make_hash(&How::<_, DefaultHasher, Cell<u32>>::new("foo"))));
+}
+
+#[test]
+fn test_hash_into() {
+    use std::hash::Hasher;
+
+    let state = RandomState::new();
+    let x = How::new_default("foo".to_owned());
+    let mut hasher = state.build_hasher();
+    How::hash_into(&x, &mut hasher);
+    assert!(How::is_hashed(&x));
+    assert_eq!(hasher.finish(), state.hash_one(&x));
+    assert_eq!(hasher.finish(), state.hash_one(Borrowed::<str>::make_ref("foo")));
+}
+
+#[test]
+fn test_borrowed_across_threads() {
+    type RHow<T> = How<T, DefaultHasher, Rc<Cell<u64>>>;
+    type RBorrowed<T> = Borrowed<T, DefaultHasher, Rc<Cell<u64>>>;
+
+    let state = RandomState::new();
+    let keys: Vec<&RBorrowed<str>> = ["foo", "bar"].map(Borrowed::make_ref).to_vec();
+    let codes: Vec<u64> = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            keys.iter()
+                .map(|key| state.hash_one(key))
+                .collect()
+        }).join().unwrap()
+    });
+    assert_eq!(codes[0], state.hash_one(RHow::new("foo".to_owned())));
+
+    #[allow(clippy::mutable_key_type)]
+    let set: HashSet<RHow<String>> = HashSet::from([How::new("foo".to_owned())]);
+    assert!(set.contains(keys[0]));
+    assert!(! set.contains(keys[1]));
+}
+
+#[test]
+fn test_from_large_map_caches() {
+    use std::collections::BTreeMap;
+
+    let map: BTreeMap<String, usize> = (0..10_000).map(|i| (i.to_string(), i)).collect();
+    let mut x: How<BTreeMap<String, usize>, CountingHasher> = map.into();
+    assert!(! How::is_hashed(&x));
+
+    let start = CountingHasher::writes();
+    let code = How::make_hash(&x);
+    let writes = CountingHasher::writes() - start;
+    assert!(writes > 10_000);
+    for _ in 0..10 {
+        assert_eq!(How::make_hash(&x), code);
+    }
+    assert_eq!(CountingHasher::writes() - start, writes);
+
+    How::make_mut(&mut x).insert("new".to_owned(), 0);
+    assert!(How::changed_since(&x, code));
+    assert!(CountingHasher::writes() - start > writes * 2);
+}
+
+#[test]
+fn test_exact_storers() {
+    use crate::{ExactAtomicU16, ExactAtomicU32, ExactAtomicU8, FromHash};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// Keep real zero codes, other codes same as `Cell<C>`
+    fn check<S, C>(rng: &mut StdRng)
+    where S: HashStorer<HashCode = C> + Default,
+          Cell<C>: HashStorer<HashCode = C> + Default,
+          C: FromHash + Eq + Debug,
+    {
+        let mut storer = S::default();
+        assert_eq!(storer.get(), None);
+        assert_eq!(storer.get_or_init(|| C::from_hash(0)), C::from_hash(0));
+        assert_eq!(storer.get(), Some(C::from_hash(0)));
+        assert_eq!(storer.get_or_init(|| unreachable!()), C::from_hash(0));
+        storer.clear();
+        assert_eq!(storer.get(), None);
+        storer.set(C::ZERO_MAPPED);
+        assert_eq!(storer.get(), Some(C::ZERO_MAPPED));
+
+        for _ in 0..4096 {
+            let hash: u64 = rng.gen();
+            let code = S::default().get_or_init(|| C::from_hash(hash));
+            assert_eq!(code, Cell::<C>::default().get_or_init(|| C::from_hash(hash)));
+        }
+    }
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    check::<Cell<Option<u8>>, u8>(&mut rng);
+    check::<Cell<Option<u16>>, u16>(&mut rng);
+    check::<Cell<Option<u32>>, u32>(&mut rng);
+    check::<ExactAtomicU8, u8>(&mut rng);
+    check::<ExactAtomicU16, u16>(&mut rng);
+    check::<ExactAtomicU32, u32>(&mut rng);
+
+    let x: How<&str, DefaultHasher, ExactAtomicU32> = How::new("foo");
+    assert_eq!(How::make_hash(&x), How::make_hash(&How::<_, DefaultHasher, AtomicU32>::new("foo")));
+    assert!(How::is_hashed(&x.clone()));
+}
+
+#[test]
+fn test_btree_map_insertion_order() {
+    use std::collections::BTreeMap;
+
+    let mut a: How<BTreeMap<&str, i32>> = How::new(BTreeMap::new());
+    let mut b: How<BTreeMap<&str, i32>> = How::new(BTreeMap::new());
+    for (k, v) in [("x", 1), ("y", 2), ("z", 3)] {
+        How::make_mut(&mut a).insert(k, v);
+    }
+    for (k, v) in [("z", 3), ("x", 1), ("y", 2)] {
+        How::make_mut(&mut b).insert(k, v);
+    }
+    assert_eq!(How::make_hash(&a), How::make_hash(&b));
+    assert_eq!(a, b);
+
+    #[allow(clippy::mutable_key_type)]
+    let set = HashSet::from([a]);
+    assert!(set.contains(&b));
+    assert!(set.contains(Borrowed::make_ref(&BTreeMap::from([("y", 2), ("z", 3), ("x", 1)]))));
+}
+
+/// Pointer casts of [`Borrowed::make_ref`] and [`Borrowed::make_mut`],
+/// run under Miri by `cargo +nightly miri test borrowed_cast`
+mod borrowed_cast {
+    use super::*;
+
+    #[test]
+    fn test_str() {
+        let s = String::from("foo");
+        let x: &Borrowed<str> = Borrowed::make_ref(s.as_str());
+        assert_eq!(&x.value, "foo");
+        assert_eq!(core::mem::size_of_val(x), 3);
+        assert_eq!(x.value.as_ptr(), s.as_ptr());
+
+        let mut s = String::from("foo");
+        let x: &mut Borrowed<str> = Borrowed::make_mut(s.as_mut_str());
+        x.value.make_ascii_uppercase();
+        assert_eq!(s, "FOO");
+    }
+
+    #[test]
+    fn test_slice() {
+        let v = [1, 2, 3];
+        let x: &Borrowed<[i32]> = Borrowed::make_ref(&v[1..]);
+        assert_eq!(x.value, [2, 3]);
+        assert_eq!(x.len(), 2);
+
+        let mut v = vec![1, 2, 3];
+        let x: &mut Borrowed<[i32]> = Borrowed::make_mut(&mut v[..2]);
+        x.value[1] = 5;
+        x.reverse();
+        assert_eq!(v, [5, 1, 3]);
+    }
+
+    #[test]
+    fn test_sized() {
+        let n = 7u64;
+        let x: &Borrowed<u64> = Borrowed::make_ref(&n);
+        assert_eq!(x.value, 7);
+        assert!(core::ptr::eq(&x.value, &n));
+
+        let mut s = String::from("foo");
+        let x: &mut Borrowed<String> = Borrowed::make_mut(&mut s);
+        x.value.push('!');
+        assert_eq!(s, "foo!");
+    }
+
+    #[test]
+    fn test_const() {
+        const X: &Borrowed<str> = Borrowed::make_ref("foo");
+        assert_eq!(&X.value, "foo");
+        assert!(HashSet::from([How::new_default("foo".to_owned())]).contains(X));
+    }
+}
+
+#[test]
+fn test_shrink_to_fit() {
+    let mut x: How<Vec<u8>, DefaultHasher, AtomicU64> = How::with_capacity(64);
+    How::make_mut(&mut x).extend_from_slice(b"foo");
+    let code = How::make_hash(&x);
+    How::shrink_to_fit(&mut x);
+    assert_eq!(How::hash_code(&x), Some(code));
+    assert!(x.capacity() < 64);
+    assert!(! How::changed_since(&x, code));
+}
+
+#[test]
+fn test_as_ptr_identity_cache() {
+    let values: Vec<How<String>> = ["foo", "bar", "foo"]
+        .map(|s| How::new(s.to_owned()))
+        .into();
+    let mut by_value: HashMap<&Borrowed<str>, usize> = HashMap::new();
+    let mut by_ptr: HashMap<*const String, usize> = HashMap::new();
+    for value in &values {
+        let len = by_value.len();
+        let id = *by_value.entry(How::probe_str(value)).or_insert(len);
+        by_ptr.insert(How::as_ptr(value), id);
+    }
+    assert_eq!(by_value.len(), 2);
+    assert_eq!(by_ptr.len(), 3);
+    assert_eq!(by_ptr[&How::as_ptr(&values[0])], by_ptr[&How::as_ptr(&values[2])]);
+    assert_ne!(by_ptr[&How::as_ptr(&values[0])], by_ptr[&How::as_ptr(&values[1])]);
+    assert!(values.iter().all(|value| ! How::is_hashed(value)));
+}
+
+#[test]
+fn test_reserve() {
+    let mut x = How::new_default("foo".to_owned());
+    let code = How::make_hash(&x);
+    How::reserve(&mut x, 64);
+    assert!(x.capacity() >= 67);
+    assert_eq!(How::hash_code(&x), Some(code));
+
+    let ptr = x.as_ptr();
+    How::make_mut(&mut x).push_str("bar");
+    assert_eq!(x.as_ptr(), ptr);
+    assert!(How::changed_since(&x, code));
+}
+
+#[cfg(all(feature = "fuzz-support", not(feature = "strict-eq")))]
+#[test]
+fn test_invariants() {
+    use arbitrary::{Arbitrary, Unstructured};
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+    use crate::invariants::{check_pair, check_set, SetOp};
+
+    type CHow = How<Vec<u8>, DefaultHasher, Cell<u8>>;
+
+    let state = RandomState::new();
+    let mut rng = StdRng::seed_from_u64(0x1f2a);
+    for _ in 0..256 {
+        let mut data = vec![0; 512];
+        rng.fill_bytes(&mut data);
+        let mut u = Unstructured::new(&data);
+
+        let Ok(a) = CHow::arbitrary(&mut u) else { continue };
+        check_pair(&state, &a, &a.clone());
+        let Ok(b) = CHow::arbitrary(&mut u) else { continue };
+        check_pair(&state, &a, &b);
+
+        let Ok(ops) = Vec::<SetOp<CHow>>::arbitrary(&mut u) else { continue };
+        let keys = ops.iter().map(|op| match op {
+            SetOp::Insert(x) | SetOp::Lookup(x) | SetOp::Remove(x) => x.clone(),
+        });
+        check_set(&state, ops.iter().cloned().chain(keys.map(SetOp::Lookup)));
+    }
+
+    let a: How<String> = How::new("foo".into());
+    check_set(&state, [
+        SetOp::Insert(a.clone()),
+        SetOp::Insert(a.clone()),
+        SetOp::Lookup(a.clone()),
+        SetOp::Remove(a.clone()),
+        SetOp::Remove(a),
+    ]);
+}
+
+#[test]
+fn test_borrow_hash_mismatch() {
+    use std::{borrow::Borrow, hash::{Hash, Hasher}};
+
+    /// Borrows as `str` but hashes more than it, breaking the `Borrow` contract
+    #[derive(PartialEq, Eq, Debug)]
+    struct Tagged(String, u32);
+    impl Hash for Tagged {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.0.hash(state);
+            self.1.hash(state);
+        }
+    }
+    impl Borrow<str> for Tagged {
+        fn borrow(&self) -> &str {
+            &self.0
+        }
+    }
+
+    let key = How::new_default(Tagged("foo".into(), 1));
+    let probe: &Borrowed<str> = Borrowed::make_ref("foo");
+    // the contract violation, a set lookup by `probe` may miss or not
+    assert_ne!(How::make_hash(&key), <Cell<u64>>::hash_one::<str, DefaultHasher>("foo"));
+    let state = RandomState::new();
+    assert_ne!(state.hash_one(&key), state.hash_one(probe));
+
+    #[allow(clippy::mutable_key_type)]
+    let mut set = HashSet::from([key]);
+    assert!(set.contains(&How::new_default(Tagged("foo".into(), 1))));
+    assert!(! set.insert(How::new_default(Tagged("foo".into(), 1))));
+
+    // upheld by String: Borrow<str>
+    #[allow(clippy::mutable_key_type)]
+    let set = HashSet::from([How::new_default("foo".to_owned())]);
+    assert!(set.contains(probe));
+}
+
+#[test]
+fn test_try_unwrap_shared() {
+    let a: Arc<How<_, DefaultHasher, AtomicU64>> = Arc::new(How::new(vec![1, 2]));
+    let weak = Arc::downgrade(&a);
+    assert_eq!(How::try_unwrap_arc(a), Ok(vec![1, 2]));
+    assert!(weak.upgrade().is_none());
+
+    let a = Rc::new(How::new_default("foo".to_owned()));
+    let b = Rc::clone(&a);
+    let a = How::try_unwrap_rc(a).unwrap_err();
+    assert!(Rc::ptr_eq(&a, &b));
+    drop(b);
+    assert_eq!(How::try_unwrap_rc(a).unwrap(), "foo");
+}
+
+#[test]
+fn test_neutral_mut() {
+    let mut x: How<String> = How::new("foo".into());
+    let code = How::make_hash(&x);
+    let old = How::neutral_mut(&mut x, |s| {
+        let old = s.capacity();
+        s.reserve(32);
+        old
+    });
+    assert!(x.capacity() >= old + 32 - 3);
+    assert_eq!(How::hash_code(&x), Some(code));
+
+    assert_eq!(How::capacity(&x), x.capacity());
+
+    How::neutral_mut(&mut x, CacheNeutralMut::shrink_to_fit);
+    assert_eq!(How::hash_code(&x), Some(code));
+    assert_eq!(How::capacity(&x), 3);
+    assert_eq!(x, How::new("foo".to_owned()));
+}
+
+mod props {
+    use std::{
+        cell::Cell,
+        collections::{hash_map::{DefaultHasher, RandomState}, BTreeMap, HashMap},
+        hash::{BuildHasher, Hash},
+        rc::Rc,
+        sync::atomic::{AtomicU32, AtomicU64},
+    };
+    use ::proptest::{collection::vec, prelude::*};
+
+    use crate::{Borrowed, FromHash, HashStorer, How, NoneStorer};
+
+    const SLOTS: usize = 4;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        Hash(usize),
+        Push(usize, String),
+        Clear(usize),
+        Clone { from: usize, to: usize },
+        Compare(usize, usize),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            (0..SLOTS).prop_map(Op::Hash),
+            (0..SLOTS, "[a-c]{1,2}").prop_map(|(i, s)| Op::Push(i, s)),
+            (0..SLOTS).prop_map(Op::Clear),
+            (0..SLOTS, 0..SLOTS).prop_map(|(from, to)| Op::Clone { from, to }),
+            (0..SLOTS, 0..SLOTS).prop_map(|(i, j)| Op::Compare(i, j)),
+        ]
+    }
+
+    /// Clone keeping the cache, atomic storers are not [`Clone`]
+    fn clone_atomic<S>(x: &How<String, DefaultHasher, S>) -> How<String, DefaultHasher, S>
+    where S: HashStorer + Default,
+    {
+        match How::hash_code(x) {
+            Some(code) => How::new_with_code(x.to_string(), code),
+            None => How::new(x.to_string()),
+        }
+    }
+
+    /// One value under every storer, and a plain [`String`] oracle
+    struct Slot {
+        oracle: String,
+        cell: How<String, DefaultHasher, Cell<u64>>,
+        atomic: How<String, DefaultHasher, AtomicU64>,
+        rc: How<String, DefaultHasher, Rc<Cell<u64>>>,
+        none: How<String, DefaultHasher, NoneStorer>,
+        narrow: How<String, DefaultHasher, Cell<u32>>,
+        narrow_atomic: How<String, DefaultHasher, AtomicU32>,
+        narrow_none: How<String, DefaultHasher, NoneStorer<u32>>,
+    }
+    macro_rules! each {
+        ($slot:expr, $x:ident => $e:expr) => {{
+            { let $x = &mut $slot.cell; $e }
+            { let $x = &mut $slot.atomic; $e }
+            { let $x = &mut $slot.rc; $e }
+            { let $x = &mut $slot.none; $e }
+            { let $x = &mut $slot.narrow; $e }
+            { let $x = &mut $slot.narrow_atomic; $e }
+            { let $x = &mut $slot.narrow_none; $e }
+        }};
+    }
+    impl Slot {
+        fn new(s: &str) -> Self {
+            Self {
+                oracle: s.to_owned(),
+                cell: How::new(s.to_owned()),
+                atomic: How::new(s.to_owned()),
+                rc: How::new(s.to_owned()),
+                none: How::new(s.to_owned()),
+                narrow: How::new(s.to_owned()),
+                narrow_atomic: How::new(s.to_owned()),
+                narrow_none: How::new(s.to_owned()),
+            }
+        }
+
+        fn clone_slot(&self) -> Self {
+            Self {
+                oracle: self.oracle.clone(),
+                cell: self.cell.clone(),
+                atomic: clone_atomic(&self.atomic),
+                rc: self.rc.clone(),
+                none: self.none.clone(),
+                narrow: self.narrow.clone(),
+                narrow_atomic: clone_atomic(&self.narrow_atomic),
+                narrow_none: self.narrow_none.clone(),
+            }
+        }
+
+        fn check(&self, state: &RandomState) {
+            let code = How::make_hash(&self.cell);
+            assert_eq!(code, How::make_hash(&How::new_default(self.oracle.clone())));
+            assert_eq!(How::make_hash(&self.atomic), code);
+            assert_eq!(How::make_hash(&self.rc), code);
+            assert_eq!(How::make_hash(&self.none), code);
+
+            let narrow = u32::from_hash(code);
+            assert_eq!(How::make_hash(&self.narrow), narrow);
+            assert_eq!(How::make_hash(&self.narrow_atomic), narrow);
+            assert_eq!(How::make_hash(&self.narrow_none), narrow);
+
+            let outer = state.hash_one(&self.cell);
+            assert_eq!(state.hash_one(&self.atomic), outer);
+            assert_eq!(state.hash_one(&self.rc), outer);
+            assert_eq!(state.hash_one(&self.none), outer);
+            assert_eq!(state.hash_one(Borrowed::<str>::make_ref(&self.oracle)), outer);
+
+            let outer = state.hash_one(&self.narrow);
+            assert_eq!(state.hash_one(&self.narrow_atomic), outer);
+            assert_eq!(state.hash_one(&self.narrow_none), outer);
+            assert_eq!(state.hash_one(Borrowed::<str, DefaultHasher, Cell<u32>>::make_ref(&self.oracle)), outer);
+        }
+
+        fn compare(&self, other: &Self) {
+            let expected = self.oracle == other.oracle;
+            assert_eq!(self.cell == other.cell, expected);
+            assert_eq!(self.atomic == other.atomic, expected);
+            assert_eq!(self.rc == other.rc, expected);
+            assert_eq!(self.none == other.none, expected);
+            assert_eq!(self.narrow == other.narrow, expected);
+            assert_eq!(self.narrow_atomic == other.narrow_atomic, expected);
+            assert_eq!(self.narrow_none == other.narrow_none, expected);
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    enum MapOp {
+        Insert(String, u8),
+        Remove(String),
+        Get(String),
+    }
+
+    fn map_op() -> impl Strategy<Value = MapOp> {
+        let key = "[a-c]{0,2}";
+        prop_oneof![
+            (key, any::<u8>()).prop_map(|(k, v)| MapOp::Insert(k, v)),
+            key.prop_map(MapOp::Remove),
+            key.prop_map(MapOp::Get),
+        ]
+    }
+
+    #[allow(clippy::mutable_key_type)]
+    fn check_map<S>(ops: &[MapOp])
+    where S: HashStorer + Default,
+          Borrowed<str, DefaultHasher, S>: Hash,
+    {
+        let mut map: HashMap<How<String, DefaultHasher, S>, u8> = HashMap::new();
+        let mut oracle = BTreeMap::new();
+
+        for op in ops {
+            match op {
+                MapOp::Insert(k, v) => {
+                    assert_eq!(map.insert(How::new(k.clone()), *v), oracle.insert(k.clone(), *v));
+                },
+                MapOp::Remove(k) => {
+                    assert_eq!(map.remove(Borrowed::make_ref(k.as_str())), oracle.remove(k));
+                },
+                MapOp::Get(k) => {
+                    assert_eq!(map.get(Borrowed::make_ref(k.as_str())), oracle.get(k));
+                    assert_eq!(map.get(&How::new(k.clone())), oracle.get(k));
+                },
+            }
+        }
+
+        let mut entries: Vec<_> = map.into_iter()
+            .map(|(k, v)| (How::into_inner(k), v))
+            .collect();
+        entries.sort();
+        assert_eq!(entries, oracle.into_iter().collect::<Vec<_>>());
+    }
+
+    proptest! {
+        #[test]
+        fn storers_agree(
+            init in vec("[a-c]{0,3}", SLOTS),
+            ops in vec(op(), 0..32),
+        ) {
+            let state = RandomState::new();
+            let mut slots: Vec<Slot> = init.iter().map(|s| Slot::new(s)).collect();
+
+            for op in &ops {
+                match *op {
+                    Op::Hash(i) => slots[i].check(&state),
+                    Op::Push(i, ref s) => {
+                        let slot = &mut slots[i];
+                        slot.oracle.push_str(s);
+                        each!(slot, x => How::make_mut(x).push_str(s));
+                    },
+                    Op::Clear(i) => {
+                        let slot = &mut slots[i];
+                        slot.oracle.clear();
+                        each!(slot, x => How::make_mut(x).clear());
+                    },
+                    Op::Clone { from, to } => slots[to] = slots[from].clone_slot(),
+                    Op::Compare(i, j) => slots[i].compare(&slots[j]),
+                }
+            }
+
+            for slot in &slots {
+                slot.check(&state);
+            }
+            for (i, j) in (0..SLOTS).flat_map(|i| (0..SLOTS).map(move |j| (i, j))) {
+                slots[i].compare(&slots[j]);
+            }
+        }
+
+        #[test]
+        fn map_matches_btree_oracle(ops in vec(map_op(), 0..64)) {
+            check_map::<Cell<u64>>(&ops);
+            check_map::<AtomicU64>(&ops);
+            check_map::<Rc<Cell<u64>>>(&ops);
+            check_map::<NoneStorer>(&ops);
+            check_map::<Cell<u32>>(&ops);
+            check_map::<NoneStorer<u32>>(&ops);
+        }
+    }
+}
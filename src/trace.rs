@@ -0,0 +1,16 @@
+//! `trace!` events of hash cache activity, enabled by `tracing` feature
+
+use core::any::type_name;
+use tracing::trace;
+
+pub(crate) fn record_computation<T: ?Sized>(code: u64) {
+    trace!(type_name = type_name::<T>(), code, "hash computed");
+}
+
+pub(crate) fn record_hit<T: ?Sized>(code: u64) {
+    trace!(type_name = type_name::<T>(), code, "hash cache hit");
+}
+
+pub(crate) fn record_invalidation<T: ?Sized>() {
+    trace!(type_name = type_name::<T>(), "hash cache invalidated");
+}
@@ -0,0 +1,94 @@
+use core::{
+    any::TypeId,
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::HashStorer;
+
+/// Storer wrapper mixing [`TypeId`] of tag `K` into the outer hash,
+/// reduces cross-type collisions in type-erased collections
+///
+/// The cached hash code is unchanged, only the outer hash is affected.
+/// [`Borrowed`] shares the storer type parameter with [`How`],
+/// so it mixes the same tag and lookup keeps working,
+/// but the outer hash is different from [`How`] with other storers
+///
+/// The tag is usually the owned value type, e.g `String` for `How<String>`,
+/// rather than `T` itself, so that `Borrowed<str>` lookups agree
+///
+/// # Examples
+/// ```
+/// # use hash_on_write::{Borrowed, How, TypeTagged};
+/// # use std::{cell::Cell, collections::{HashSet, hash_map::{DefaultHasher, RandomState}}, hash::BuildHasher};
+/// type S<K> = TypeTagged<Cell<u64>, K>;
+/// let a: How<String, DefaultHasher, S<String>> = How::new("a".into());
+/// let b: How<&str, DefaultHasher, S<&str>> = How::new("a");
+/// assert_eq!(How::make_hash(&a), How::make_hash(&b));
+///
+/// let state = RandomState::new();
+/// assert_ne!(state.hash_one(&a), state.hash_one(&b));
+///
+/// let set = HashSet::from([a]);
+/// assert!(set.contains(Borrowed::<str, DefaultHasher, S<String>>::make_ref("a")));
+/// ```
+///
+/// [`How`]: crate::How
+/// [`Borrowed`]: crate::Borrowed
+pub struct TypeTagged<S, K: ?Sized> {
+    _tag: PhantomData<fn() -> *const K>,
+    pub storer: S,
+}
+impl<S, K: ?Sized> TypeTagged<S, K> {
+    /// New a tagged storer
+    pub fn new(storer: S) -> Self {
+        Self { _tag: PhantomData, storer }
+    }
+}
+impl<S: Default, K: ?Sized> Default for TypeTagged<S, K> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+impl<S: Clone, K: ?Sized> Clone for TypeTagged<S, K> {
+    fn clone(&self) -> Self {
+        Self::new(self.storer.clone())
+    }
+}
+impl<S: Debug, K: ?Sized> Debug for TypeTagged<S, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeTagged")
+            .field("tag", &core::any::type_name::<K>())
+            .field("storer", &self.storer)
+            .finish()
+    }
+}
+impl<S: HashStorer, K: ?Sized + 'static> HashStorer for TypeTagged<S, K> {
+    type HashCode = S::HashCode;
+
+    const CACHES: bool = S::CACHES;
+
+    fn clear(&mut self) {
+        self.storer.clear()
+    }
+
+    fn get(&self) -> Option<Self::HashCode> {
+        self.storer.get()
+    }
+
+    fn set(&self, code: Self::HashCode) {
+        self.storer.set(code)
+    }
+
+    fn get_or_init<F>(&self, f: F) -> Self::HashCode
+    where F: FnOnce() -> Self::HashCode,
+    {
+        self.storer.get_or_init(f)
+    }
+
+    fn write_code<H: Hasher>(code: Self::HashCode, state: &mut H) {
+        TypeId::of::<K>().hash(state);
+        S::write_code(code, state)
+    }
+}
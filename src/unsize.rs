@@ -0,0 +1,27 @@
+use core::ops::CoerceUnsized;
+
+use crate::{HashStorer, How};
+
+impl<T, H, S: HashStorer> How<T, H, S> {
+    /// Unsizing coerce the wrapped value, e.g `How<Box<[T; N]>>` to `How<Box<[T]>>`,
+    /// and clear the hash cache, because the coerced type may hash differently
+    ///
+    /// `How` does not implement [`CoerceUnsized`],
+    /// the builtin coercion is a bitwise copy which cannot clear the hash cache
+    ///
+    /// # Examples
+    /// ```
+    /// # use hash_on_write::How;
+    /// let x: How<Box<[i32; 2]>> = How::new(Box::new([1, 2]));
+    /// How::make_hash(&x);
+    /// let y: How<Box<[i32]>> = How::unsize(x);
+    /// assert!(! How::is_hashed(&y));
+    /// assert_eq!(**y, [1, 2]);
+    /// ```
+    pub fn unsize<U>(this: Self) -> How<U, H, S>
+    where T: CoerceUnsized<U>,
+    {
+        let How { hashcode, value, .. } = this;
+        How::new_in(value, hashcode)
+    }
+}
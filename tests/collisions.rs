@@ -0,0 +1,57 @@
+//! Counter is process-wide, keep this file as only one test
+#![cfg(all(feature = "collisions", not(feature = "strict-eq")))]
+
+use std::{
+    hash::Hasher,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use hash_on_write::{collision_count, reset_collision_count, set_collision_callback, How};
+
+/// All values collide
+#[derive(Default)]
+struct ConstHasher;
+impl Hasher for ConstHasher {
+    fn finish(&self) -> u64 {
+        1
+    }
+
+    fn write(&mut self, _bytes: &[u8]) { }
+}
+
+static CALLED: AtomicU64 = AtomicU64::new(0);
+
+#[test]
+fn test_collision_count() {
+    type CHow<T> = How<T, ConstHasher>;
+    reset_collision_count();
+    set_collision_callback(Some(|| { CALLED.fetch_add(1, Ordering::Relaxed); }));
+
+    let a = CHow::new("foo");
+    let b = CHow::new("bar");
+    let c = CHow::new("foo");
+
+    assert_ne!(a, b);
+    assert_eq!(collision_count(), 0);
+
+    How::make_hash(&a);
+    How::make_hash(&b);
+    How::make_hash(&c);
+    assert_eq!(How::hash_code(&a), How::hash_code(&b));
+
+    assert_ne!(a, b);
+    assert_eq!(collision_count(), 1);
+    assert_eq!(a, c);
+    assert_eq!(collision_count(), 1);
+    assert_ne!(b, c);
+    assert_eq!(collision_count(), 2);
+    assert_eq!(CALLED.load(Ordering::Relaxed), 2);
+
+    set_collision_callback(None);
+    assert_ne!(a, b);
+    assert_eq!(collision_count(), 3);
+    assert_eq!(CALLED.load(Ordering::Relaxed), 2);
+
+    reset_collision_count();
+    assert_eq!(collision_count(), 0);
+}
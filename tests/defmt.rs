@@ -0,0 +1,44 @@
+#![cfg(feature = "defmt")]
+
+use std::{cell::RefCell, collections::hash_map::DefaultHasher};
+use hash_on_write::{Borrowed, How};
+
+thread_local! {
+    static OUTPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+#[defmt::global_logger]
+struct Logger;
+
+unsafe impl defmt::Logger for Logger {
+    fn acquire() {}
+    unsafe fn flush() {}
+    unsafe fn release() {}
+    unsafe fn write(bytes: &[u8]) {
+        OUTPUT.with(|output| output.borrow_mut().extend_from_slice(bytes))
+    }
+}
+
+defmt::timestamp!("{=u32}", 0);
+
+fn output() -> Vec<u8> {
+    OUTPUT.with(|output| output.take())
+}
+
+#[test]
+fn test_format() {
+    let x: How<&str, DefaultHasher> = How::new("foo");
+    defmt::println!("{}", x);
+    let uncached = output();
+    assert!(! uncached.is_empty());
+
+    defmt::println!("{}", Borrowed::<str>::make_ref("foo"));
+    let borrowed = output();
+    assert_eq!(borrowed.len(), uncached.len());
+
+    let code = How::make_hash(&x);
+    defmt::println!("{}", x);
+    let cached = output();
+    assert!(cached.len() > uncached.len());
+    assert!(cached.windows(8).any(|bytes| bytes == code.to_le_bytes()));
+}
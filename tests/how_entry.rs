@@ -0,0 +1,42 @@
+//! [`how_entry`] on hashbrown [`HashMap`], the update-existing path must not allocate
+
+#![cfg(feature = "hashbrown")]
+
+mod common;
+
+use hash_on_write::{how_entry, How};
+use hashbrown::{hash_map::RawEntryMut, HashMap};
+
+use common::allocations;
+
+fn count(map: &mut HashMap<How<String>, usize>, word: &str) {
+    let (_, n) = how_entry(map, word)
+        .or_insert_with(|| (How::new(word.to_owned()), 0));
+    *n += 1;
+}
+
+#[test]
+fn test_how_entry() {
+    let mut map: HashMap<How<String>, usize> = HashMap::with_capacity(16);
+    let words = ["foo", "bar", "", "foo", "baz", "bar", "foo"];
+
+    for word in words {
+        count(&mut map, word);
+    }
+    assert_eq!(map.len(), 4);
+    assert_eq!(map[&How::new("foo".to_owned())], 3);
+
+    let before = allocations();
+    for word in words {
+        count(&mut map, word);
+    }
+    assert_eq!(allocations(), before);
+    assert_eq!(map[&How::new("foo".to_owned())], 6);
+
+    match how_entry(&mut map, "qux") {
+        RawEntryMut::Vacant(entry) => { entry.insert(How::new("qux".to_owned()), 1); },
+        RawEntryMut::Occupied(_) => unreachable!(),
+    }
+    assert!(allocations() > before);
+    assert_eq!(map.len(), 5);
+}
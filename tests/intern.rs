@@ -0,0 +1,46 @@
+//! String interner on hashbrown [`HashSet`], the hit path must not allocate
+//!
+//! [`Borrowed`] is [`Equivalent`] to [`How`] by hashbrown's blanket impl,
+//! because `How<String>: Borrow<Borrowed<str>>`
+//!
+//! [`Equivalent`]: hashbrown::Equivalent
+
+mod common;
+
+use hash_on_write::{Borrowed, How};
+use hashbrown::HashSet;
+
+use common::allocations;
+
+#[derive(Default)]
+struct Interner {
+    set: HashSet<How<String>>,
+}
+impl Interner {
+    fn intern(&mut self, s: &str) -> &str {
+        self.set.get_or_insert_with(Borrowed::make_ref(s), |s| {
+            How::new(s.value.to_owned())
+        })
+    }
+}
+
+#[test]
+fn test_intern() {
+    let mut interner = Interner::default();
+    let words = ["foo", "bar", "", "foo", "baz", "bar", "foo"];
+
+    for word in words {
+        assert_eq!(interner.intern(word), word);
+    }
+    assert_eq!(interner.set.len(), 4);
+
+    let before = allocations();
+    for word in words {
+        let ptr = interner.intern(word).as_ptr();
+        assert_eq!(ptr, interner.intern(word).as_ptr());
+    }
+    assert_eq!(allocations(), before);
+
+    interner.intern("qux");
+    assert!(allocations() > before);
+}
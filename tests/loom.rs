@@ -0,0 +1,76 @@
+//! Model checked races of the atomic storers,
+//! run by `RUSTFLAGS="--cfg loom" cargo test --release --test loom`
+
+#![cfg(loom)]
+
+use std::{collections::hash_map::DefaultHasher, sync::Arc};
+use hash_on_write::How;
+use loom::{sync::atomic::AtomicU64, thread};
+
+type LHow<T> = How<T, DefaultHasher, AtomicU64>;
+type SHow<T> = How<T, DefaultHasher, Arc<AtomicU64>>;
+
+fn expected(s: &str) -> u64 {
+    How::make_hash(&How::new_default(s))
+}
+
+/// Every observed code is the zero-mapped hash of the value
+fn check<S>(x: &How<&str, DefaultHasher, S>, expected: u64)
+where S: hash_on_write::HashStorer<HashCode = u64>,
+{
+    if let Some(code) = How::hash_code(x) {
+        assert_eq!(code, expected);
+    }
+}
+
+fn race_make_hash(threads: usize) {
+    loom::model(move || {
+        let expected = expected("foo");
+        let x: Arc<LHow<&str>> = Arc::new(How::new("foo"));
+
+        let handles: Vec<_> = (0..threads).map(|_| {
+            let x = x.clone();
+            thread::spawn(move || {
+                check(&x, expected);
+                assert_eq!(How::make_hash(&x), expected);
+                check(&x, expected);
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(How::hash_code(&x), Some(expected));
+    });
+}
+
+#[test]
+fn test_two_threads_make_hash() {
+    race_make_hash(2);
+}
+
+#[test]
+fn test_three_threads_make_hash() {
+    race_make_hash(3);
+}
+
+#[test]
+fn test_clear_races_make_hash() {
+    loom::model(|| {
+        let (foo, bar) = (expected("foo"), expected("bar"));
+        let a: SHow<&str> = How::new("foo");
+        let mut b = a.clone();
+
+        let reader = thread::spawn(move || {
+            assert_eq!(How::make_hash(&a), foo);
+            a
+        });
+        check(&b, foo);
+        *How::make_mut(&mut b) = "bar";
+        check(&b, bar);
+        assert_eq!(How::make_hash(&b), bar);
+
+        let a = reader.join().unwrap();
+        assert_eq!(How::hash_code(&a), Some(foo));
+        assert_eq!(How::hash_code(&b), Some(bar));
+    });
+}
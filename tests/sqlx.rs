@@ -0,0 +1,43 @@
+#![cfg(feature = "sqlx-sqlite")]
+
+use std::collections::HashMap;
+use hash_on_write::{Borrowed, How};
+use sqlx::{sqlite::SqlitePool, FromRow};
+
+#[derive(Debug, FromRow)]
+struct Row {
+    name: How<String>,
+    count: How<i64>,
+}
+
+#[tokio::test]
+async fn test_sqlite_round_trip() {
+    let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+    sqlx::query("CREATE TABLE t (name TEXT NOT NULL, count INTEGER NOT NULL)")
+        .execute(&pool).await.unwrap();
+
+    for (name, count) in [("foo", 1), ("bar", 2)] {
+        let name: How<String> = How::new(name.to_owned());
+        How::make_hash(&name);
+        sqlx::query("INSERT INTO t VALUES (?, ?)")
+            .bind(&name)
+            .bind(How::<i64>::new(count))
+            .execute(&pool).await.unwrap();
+    }
+
+    let rows: Vec<Row> = sqlx::query_as("SELECT name, count FROM t ORDER BY name")
+        .fetch_all(&pool).await.unwrap();
+    assert!(rows.iter().all(|row| ! How::is_hashed(&row.name)));
+
+    #[allow(clippy::mutable_key_type)]
+    let map: HashMap<How<String>, i64> = rows.into_iter()
+        .map(|row| (row.name, *row.count))
+        .collect();
+    assert_eq!(map.get(Borrowed::make_ref("foo")), Some(&1));
+    assert_eq!(map.get(Borrowed::make_ref("bar")), Some(&2));
+
+    let (name,): (How<String>,) = sqlx::query_as("SELECT name FROM t WHERE count = ?")
+        .bind(2)
+        .fetch_one(&pool).await.unwrap();
+    assert_eq!(*name, "bar");
+}
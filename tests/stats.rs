@@ -0,0 +1,40 @@
+//! Counters are process-wide, keep this file as only one test
+#![cfg(feature = "stats")]
+
+use std::{cell::Cell, collections::{hash_map::DefaultHasher, HashSet}};
+
+use hash_on_write::{reset_stats, stats, Borrowed, CachePoisonDetector, How, Stats};
+
+#[test]
+fn test_stats() {
+    reset_stats();
+    assert_eq!(stats(), Stats::default());
+
+    let mut a = How::new_default("foo".to_owned());
+    let b = How::new_default("bar".to_owned());
+
+    How::make_hash(&a);
+    How::make_hash(&a);
+    How::make_hash(&b);
+    assert_eq!(stats(), Stats { computations: 2, hits: 1, invalidations: 0 });
+
+    How::make_mut(&mut a).push('!');
+    How::make_hash(&a);
+    assert_eq!(stats(), Stats { computations: 3, hits: 1, invalidations: 1 });
+
+    #[allow(clippy::mutable_key_type)]
+    let mut set = HashSet::new();
+    set.insert(a);
+    set.insert(b);
+    assert!(set.contains(Borrowed::make_ref("foo!")));
+    assert_eq!(stats(), Stats { computations: 4, hits: 3, invalidations: 1 });
+
+    reset_stats();
+    let c: How<&str, DefaultHasher, CachePoisonDetector<Cell<u64>>> = How::new("baz");
+    How::make_hash(&c);
+    How::make_hash(&c);
+    assert_eq!(stats(), Stats { computations: 1, hits: 1, invalidations: 0 });
+
+    reset_stats();
+    assert_eq!(stats(), Stats::default());
+}